@@ -0,0 +1,151 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::{self, Display};
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering::Relaxed};
+
+/// The system allocator, counting allocations once [`enable`] is called.
+///
+/// The counters are process-wide, so anything allocating on other threads
+/// while a closure is tracked, such as a part left running after a timeout,
+/// is counted too.
+pub struct Counting;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+/// Live bytes allocated since the last reset, minus those freed. Negative
+/// when memory from before the reset is freed.
+static LIVE: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+
+fn record(allocated: usize, freed: usize) {
+    if allocated > 0 {
+        ALLOCATIONS.fetch_add(1, Relaxed);
+        BYTES.fetch_add(allocated, Relaxed);
+    }
+    let change = allocated as isize - freed as isize;
+    let live = LIVE.fetch_add(change, Relaxed) + change;
+    PEAK.fetch_max(live, Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() && ENABLED.load(Relaxed) {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() && ENABLED.load(Relaxed) {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        if ENABLED.load(Relaxed) {
+            record(0, layout.size());
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() && ENABLED.load(Relaxed) {
+            record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// Starts counting allocations, for the rest of the process.
+pub fn enable() {
+    ENABLED.store(true, Relaxed);
+}
+
+/// Allocations made while running a closure.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocations: usize,
+    pub bytes: usize,
+    /// Highest number of bytes allocated by the closure and live at once.
+    pub peak: usize,
+}
+
+/// Runs `f`, also returning its allocations if counting is enabled.
+pub fn track<F, O>(f: F) -> (O, Option<AllocStats>)
+where
+    F: FnOnce() -> O,
+{
+    if !ENABLED.load(Relaxed) {
+        return (f(), None);
+    }
+
+    ALLOCATIONS.store(0, Relaxed);
+    BYTES.store(0, Relaxed);
+    LIVE.store(0, Relaxed);
+    PEAK.store(0, Relaxed);
+    let result = f();
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Relaxed),
+        bytes: BYTES.load(Relaxed),
+        peak: PEAK.load(Relaxed).max(0) as usize,
+    };
+    (result, Some(stats))
+}
+
+/// Formats `bytes` in the largest unit it has more than one of.
+pub fn format_bytes(bytes: usize) -> String {
+    const KIB: usize = 1 << 10;
+    const MIB: usize = 1 << 20;
+    if bytes > MIB {
+        format!("{} MiB", bytes / MIB)
+    } else if bytes > KIB {
+        format!("{} KiB", bytes / KIB)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.allocations,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting() {
+        let stats = AllocStats {
+            allocations: 3,
+            bytes: 5 << 20,
+            peak: 1536,
+        };
+        assert_eq!(
+            stats.to_string(),
+            "3 allocations, 5 MiB allocated, 1 KiB peak"
+        );
+        assert_eq!(format_bytes(1024), "1024 B");
+    }
+
+    #[test]
+    fn counts_when_enabled() {
+        enable();
+        let (v, stats) = track(|| vec![0u8; 4096]);
+        let stats = stats.unwrap();
+        // Other tests allocate concurrently, so only lower bounds hold.
+        assert!(stats.allocations >= 1);
+        assert!(stats.bytes >= v.len());
+    }
+}
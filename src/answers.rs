@@ -0,0 +1,121 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::path::PathBuf;
+
+/// Known-correct answers for one year, read from `answers/{year}.toml`:
+///
+/// ```toml
+/// [day08]
+/// part1 = 1785
+/// part2 = "345168"
+/// ```
+#[derive(Default)]
+pub struct Answers {
+    days: HashMap<String, DayAnswers>,
+}
+
+/// Known answers for a single input. Also the format of the sample
+/// sidecar files, e.g. `samples/2022/day09-2.expected`.
+#[derive(Deserialize, Default)]
+pub struct DayAnswers {
+    part1: Option<toml::Value>,
+    part2: Option<toml::Value>,
+}
+
+impl DayAnswers {
+    pub fn expected(&self, part: u32) -> Option<String> {
+        let value = match part {
+            1 => self.part1.as_ref()?,
+            _ => self.part2.as_ref()?,
+        };
+        match value {
+            toml::Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+
+    pub fn verdict(&self, part: u32, actual: &str) -> Verdict {
+        match self.expected(part) {
+            Some(expected) if expected == actual => Verdict::Pass,
+            Some(expected) => Verdict::Fail(expected),
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+impl Answers {
+    pub fn path(year: u32) -> PathBuf {
+        PathBuf::from(format!("answers/{}.toml", year))
+    }
+
+    /// Loads the answers for `year`; a missing file has no known answers.
+    pub fn load(year: u32) -> Result<Self, String> {
+        let path = Self::path(year);
+        match std::fs::read_to_string(&path) {
+            Ok(raw) => Self::from_toml(&raw).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn from_toml(raw: &str) -> Result<Self, toml::de::Error> {
+        Ok(Self {
+            days: toml::from_str(raw)?,
+        })
+    }
+
+    pub fn verdict(&self, day: u32, part: u32, actual: &str) -> Verdict {
+        match self.days.get(&format!("day{:02}", day)) {
+            Some(answers) => answers.verdict(part, actual),
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    /// Holds the expected answer.
+    Fail(String),
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "PASS"),
+            Self::Fail(expected) => write!(f, "FAIL, expected {}", expected),
+            Self::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verdicts() {
+        let answers = Answers::from_toml(
+            r#"
+            [day08]
+            part1 = 1785
+            part2 = "345168"
+
+            [day14]
+            part1 = 24
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(answers.verdict(8, 1, "1785"), Verdict::Pass);
+        assert_eq!(answers.verdict(8, 2, "345168"), Verdict::Pass);
+        assert_eq!(
+            answers.verdict(14, 1, "25"),
+            Verdict::Fail(String::from("24"))
+        );
+        assert_eq!(answers.verdict(14, 2, "93"), Verdict::Unknown);
+        assert_eq!(answers.verdict(1, 1, "0"), Verdict::Unknown);
+    }
+}
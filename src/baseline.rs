@@ -0,0 +1,213 @@
+use crate::harness::DEFAULT_IMPLEMENTATION;
+use crate::report::DayReport;
+use crate::timing::format_duration;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::path::Path;
+use std::time::Duration;
+
+/// Per-day timings saved from an earlier run, to compare later runs against.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Baseline {
+    pub days: Vec<DayTimings>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DayTimings {
+    pub year: u32,
+    pub day: u32,
+    /// Left out for the default implementation.
+    #[serde(
+        default = "default_implementation",
+        skip_serializing_if = "is_default_implementation"
+    )]
+    pub implementation: String,
+    pub parse_ns: u64,
+    pub part1_ns: Option<u64>,
+    pub part2_ns: Option<u64>,
+}
+
+fn default_implementation() -> String {
+    String::from(DEFAULT_IMPLEMENTATION)
+}
+
+fn is_default_implementation(name: &str) -> bool {
+    name == DEFAULT_IMPLEMENTATION
+}
+
+impl DayTimings {
+    fn key(&self) -> (u32, u32, &str) {
+        (self.year, self.day, &self.implementation)
+    }
+
+    fn from_report(report: &DayReport) -> Option<Self> {
+        let results = report.result.as_ref().ok()?;
+        let nanos = |d: Duration| d.as_nanos() as u64;
+        let part = |n: u32| {
+            results
+                .parts
+                .iter()
+                .find(|p| p.part == n && p.answer.is_ok())
+                .map(|p| nanos(p.time.median))
+        };
+        Some(Self {
+            year: report.year,
+            day: report.day,
+            implementation: report.implementation.to_string(),
+            parse_ns: nanos(results.parse_time.median),
+            part1_ns: part(1),
+            part2_ns: part(2),
+        })
+    }
+
+    fn entries(&self) -> [(&'static str, Option<u64>); 3] {
+        [
+            ("parse", Some(self.parse_ns)),
+            ("part 1", self.part1_ns),
+            ("part 2", self.part2_ns),
+        ]
+    }
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let raw =
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        serde_json::from_str(&raw).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Like [`Baseline::load`], with a missing file as an empty baseline, for
+    /// starting one.
+    pub fn load_or_default(path: &Path) -> Result<Self, String> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).expect("baseline is serializable");
+        std::fs::write(path, json + "\n").map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Replaces the timings of every day that ran in `reports`.
+    pub fn update(&mut self, reports: &[DayReport]) {
+        for timings in reports.iter().filter_map(DayTimings::from_report) {
+            self.days.retain(|d| d.key() != timings.key());
+            self.days.push(timings);
+        }
+        self.days.sort_unstable_by(|a, b| a.key().cmp(&b.key()));
+    }
+
+    /// Compares every timing present in both the baseline and `reports`.
+    pub fn compare(&self, reports: &[DayReport], threshold_percent: f64) -> Vec<Comparison> {
+        let mut comparisons = Vec::new();
+        for current in reports.iter().filter_map(DayTimings::from_report) {
+            let Some(baseline) = self.days.iter().find(|d| d.key() == current.key()) else {
+                continue;
+            };
+
+            for ((what, before), (_, after)) in
+                baseline.entries().into_iter().zip(current.entries())
+            {
+                let (Some(before), Some(after)) = (before, after) else {
+                    continue;
+                };
+                let change = (after as f64 - before as f64) / (before.max(1) as f64) * 100.0;
+                comparisons.push(Comparison {
+                    year: current.year,
+                    day: current.day,
+                    implementation: current.implementation.clone(),
+                    what,
+                    before: Duration::from_nanos(before),
+                    after: Duration::from_nanos(after),
+                    change_percent: change,
+                    regressed: change > threshold_percent,
+                });
+            }
+        }
+        comparisons
+    }
+}
+
+pub struct Comparison {
+    pub year: u32,
+    pub day: u32,
+    pub implementation: String,
+    /// `parse`, `part 1` or `part 2`.
+    pub what: &'static str,
+    pub before: Duration,
+    pub after: Duration,
+    pub change_percent: f64,
+    pub regressed: bool,
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} day {} ", self.year, self.day)?;
+        if self.implementation != DEFAULT_IMPLEMENTATION {
+            write!(f, "({}) ", self.implementation)?;
+        }
+        write!(
+            f,
+            "{}: {} -> {} ({:+.1}%)",
+            self.what,
+            format_duration(self.before),
+            format_duration(self.after),
+            self.change_percent
+        )?;
+        if self.regressed {
+            write!(f, " REGRESSED")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::fixture::report;
+
+    #[test]
+    fn update_and_compare() {
+        let mut baseline = Baseline::default();
+        baseline.update(&[
+            report(14, DEFAULT_IMPLEMENTATION, vec![Ok("")], 10, 100),
+            report(11, DEFAULT_IMPLEMENTATION, vec![Ok("")], 10, 100),
+        ]);
+        baseline.update(&[report(14, DEFAULT_IMPLEMENTATION, vec![Ok("")], 10, 200)]);
+        assert_eq!(baseline.days.len(), 2);
+        assert_eq!(baseline.days[0].day, 11);
+        assert_eq!(baseline.days[1].part1_ns, Some(200_000));
+        assert_eq!(baseline.days[1].part2_ns, None);
+
+        let comparisons = baseline.compare(
+            &[
+                report(14, DEFAULT_IMPLEMENTATION, vec![Ok("")], 10, 250),
+                report(7, DEFAULT_IMPLEMENTATION, vec![Ok("")], 1, 1),
+            ],
+            20.0,
+        );
+        assert_eq!(comparisons.len(), 2);
+        assert!(!comparisons[0].regressed);
+        assert!(comparisons[1].regressed);
+        assert_eq!(
+            comparisons[1].to_string(),
+            "2022 day 14 part 1: 200 \u{00b5}s -> 250 \u{00b5}s (+25.0%) REGRESSED"
+        );
+    }
+
+    #[test]
+    fn missing_file() {
+        let path = std::env::temp_dir().join(format!("aoc-baseline-{}", std::process::id()));
+        assert!(Baseline::load(&path).is_err());
+        assert!(Baseline::load_or_default(&path).unwrap().days.is_empty());
+
+        let mut baseline = Baseline::default();
+        baseline.update(&[report(1, DEFAULT_IMPLEMENTATION, vec![Ok("")], 10, 100)]);
+        baseline.save(&path).unwrap();
+        assert_eq!(Baseline::load_or_default(&path).unwrap().days.len(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,564 @@
+pub const USAGE: &str = "\
+Usage: advent-of-code-2022 [YEAR] [OPTIONS]
+       advent-of-code-2022 new --year <YEAR> --day <DAY>
+       advent-of-code-2022 fetch [YEAR] [--day <DAYS>]
+       advent-of-code-2022 submit --year <YEAR> --day <DAY> --part <1|2> [--answer <ANSWER>]
+       advent-of-code-2022 generate --year <YEAR> --day <DAY> --size <N> [--seed <N>] [--output <PATH>]
+
+Commands:
+  new                 Generate a day module, its registration and a sample
+  fetch               Download missing inputs of the selected days to input/,
+                      using the session token from AOC_SESSION or aoc.toml
+  submit              Solve a part and submit its answer, or the given one,
+                      logging the outcome to answers/submissions.jsonl
+  generate            Write a random input of a day, growing with --size, to
+                      stdout or a file; the same --seed (default: 0) gives
+                      the same input
+
+Options:
+  --year <YEAR|all>   Year to run (default: 2022)
+  --day <DAYS>        Days to run, e.g. 3, 1,4,6 or 3..=7 (default: all)
+  --part <1|2>        Only run the given part (default: both)
+  --input <PATH|->    Read input from a file or stdin instead of input/
+  --check             Compare answers against answers/<YEAR>.toml
+  --verbose           Show answers in full, e.g. the CRT render behind letters
+                      or the route behind a number of steps
+  --impl <NAME|all>   Run another implementation of the days that have it, or
+                      every implementation, comparing answers and timings
+  --param <NAME=VALUE>
+                      Override a puzzle constant of the selected days, e.g.
+                      rounds=500; answers are then not checked
+  --bench <N>         Time N runs of each parse and part and report statistics
+  --format <FORMAT>   Output as text, json or csv (default: text)
+  --save-baseline <PATH>
+                      Save per-day timings to a baseline file
+  --compare <PATH>    Compare timings against a saved baseline
+  --threshold <PCT>   Slowdown reported as a regression (default: 10)
+  --parallel          Run days concurrently, printing them in order
+  --fetch             Download missing inputs before running
+  --alloc             Count allocations, bytes allocated and peak live bytes
+                      of each parse and part
+  --timeout <[YEAR/DAY=]TIME>
+                      Report parts running longer than e.g. 500ms, 30s or 2m
+                      as TIMEOUT, for every day or just YEAR/DAY";
+
+use advent_of_code_2022::harness::{Config, InputSource, Timeouts, DEFAULT_IMPLEMENTATION};
+use advent_of_code_2022::params::Params;
+use advent_of_code_2022::report::Format;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub struct Options {
+    /// `None` runs every registered year.
+    pub year: Option<u32>,
+    /// `None` runs every registered day.
+    pub days: Option<Vec<u32>>,
+    pub config: Config,
+    pub format: Format,
+    pub save_baseline: Option<PathBuf>,
+    pub compare: Option<PathBuf>,
+    /// Percentage slowdown against the baseline that counts as a regression.
+    pub threshold: f64,
+    pub parallel: bool,
+    /// Download missing inputs before running.
+    pub fetch: bool,
+    /// Count allocations while parsing and solving.
+    pub alloc: bool,
+    pub implementation: Implementation,
+}
+
+/// Which implementations of each selected day to run.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Implementation {
+    #[default]
+    Default,
+    /// The named implementation where a day has one, its default elsewhere.
+    Named(String),
+    All,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            year: Some(2022),
+            days: None,
+            config: Config::default(),
+            format: Format::default(),
+            save_baseline: None,
+            compare: None,
+            threshold: 10.0,
+            parallel: false,
+            fetch: false,
+            alloc: false,
+            implementation: Implementation::default(),
+        }
+    }
+}
+
+impl Options {
+    pub fn includes_year(&self, year: u32) -> bool {
+        self.year.is_none_or(|y| y == year)
+    }
+
+    pub fn includes_day(&self, day: u32) -> bool {
+        self.days.as_ref().is_none_or(|d| d.contains(&day))
+    }
+}
+
+pub enum Command {
+    Run(Options),
+    /// Scaffold a new day module.
+    New {
+        year: u32,
+        day: u32,
+    },
+    /// Download the inputs of the days selected by the options.
+    Fetch(Options),
+    /// Submit the answer of one part, computing it unless given.
+    Submit {
+        year: u32,
+        day: u32,
+        part: u32,
+        answer: Option<String>,
+    },
+    /// Write a random input for one day.
+    Generate {
+        year: u32,
+        day: u32,
+        size: usize,
+        seed: u64,
+        /// `None` writes to stdout.
+        output: Option<PathBuf>,
+    },
+}
+
+pub fn parse_command<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter().peekable();
+    match args.peek().map(String::as_str) {
+        Some("new") => {
+            args.next();
+            parse_new(args)
+        }
+        Some("fetch") => {
+            args.next();
+            parse_fetch(args)
+        }
+        Some("submit") => {
+            args.next();
+            parse_submit(args)
+        }
+        Some("generate") => {
+            args.next();
+            parse_generate(args)
+        }
+        _ => parse_args(args).map(Command::Run),
+    }
+}
+
+/// Splits `--flag=value` arguments so values can also be the next argument.
+struct Args<I> {
+    args: I,
+    inline_value: Option<String>,
+}
+
+impl<I: Iterator<Item = String>> Args<I> {
+    fn new(args: I) -> Self {
+        Self {
+            args,
+            inline_value: None,
+        }
+    }
+
+    fn next_flag(&mut self) -> Option<String> {
+        let arg = self.args.next()?;
+        match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                self.inline_value = Some(value.to_string());
+                Some(flag.to_string())
+            }
+            _ => {
+                self.inline_value = None;
+                Some(arg)
+            }
+        }
+    }
+
+    fn value(&mut self, flag: &str) -> Result<String, String> {
+        self.inline_value
+            .take()
+            .or_else(|| self.args.next())
+            .ok_or_else(|| format!("Missing value for {}", flag))
+    }
+}
+
+fn parse_new<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = Args::new(args);
+    let (mut year, mut day) = (None, None);
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--year" | "-y" => year = parse_year(&args.value("--year")?)?,
+            "--day" | "-d" => day = Some(parse_day(&args.value("--day")?)?),
+            "--help" | "-h" => return Err(String::from(USAGE)),
+            unknown => return Err(format!("Unknown argument: {}", unknown)),
+        }
+    }
+
+    match (year, day) {
+        (Some(year), Some(day)) => Ok(Command::New { year, day }),
+        _ => Err(String::from("new requires --year and --day")),
+    }
+}
+
+fn parse_fetch<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut options = Options::default();
+    let mut args = Args::new(args);
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--year" | "-y" => options.year = parse_year(&args.value("--year")?)?,
+            "--day" | "-d" => options.days = Some(parse_days(&args.value("--day")?)?),
+            "--help" | "-h" => return Err(String::from(USAGE)),
+            positional if !positional.starts_with('-') => options.year = parse_year(positional)?,
+            unknown => return Err(format!("Unknown argument: {}", unknown)),
+        }
+    }
+    Ok(Command::Fetch(options))
+}
+
+fn parse_submit<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = Args::new(args);
+    let (mut year, mut day, mut part, mut answer) = (None, None, None, None);
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--year" | "-y" => year = parse_year(&args.value("--year")?)?,
+            "--day" | "-d" => day = Some(parse_day(&args.value("--day")?)?),
+            "--part" | "-p" => part = Some(parse_part(&args.value("--part")?)?),
+            "--answer" | "-a" => answer = Some(args.value("--answer")?),
+            "--help" | "-h" => return Err(String::from(USAGE)),
+            unknown => return Err(format!("Unknown argument: {}", unknown)),
+        }
+    }
+
+    match (year, day, part) {
+        (Some(year), Some(day), Some(part)) => Ok(Command::Submit {
+            year,
+            day,
+            part,
+            answer,
+        }),
+        _ => Err(String::from("submit requires --year, --day and --part")),
+    }
+}
+
+fn parse_generate<I>(args: I) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut args = Args::new(args);
+    let (mut year, mut day, mut size, mut seed, mut output) = (None, None, None, 0, None);
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--year" | "-y" => year = parse_year(&args.value("--year")?)?,
+            "--day" | "-d" => day = Some(parse_day(&args.value("--day")?)?),
+            "--size" | "-s" => size = Some(parse_number(&args.value("--size")?, "size")?),
+            "--seed" => seed = parse_number(&args.value("--seed")?, "seed")?,
+            "--output" | "-o" => output = Some(args.value("--output")?.into()),
+            "--help" | "-h" => return Err(String::from(USAGE)),
+            unknown => return Err(format!("Unknown argument: {}", unknown)),
+        }
+    }
+
+    match (year, day, size) {
+        (Some(year), Some(day), Some(size)) => Ok(Command::Generate {
+            year,
+            day,
+            size,
+            seed,
+            output,
+        }),
+        _ => Err(String::from("generate requires --year, --day and --size")),
+    }
+}
+
+pub fn parse_args<I>(args: I) -> Result<Options, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut options = Options::default();
+    let mut args = Args::new(args.into_iter());
+
+    while let Some(flag) = args.next_flag() {
+        let mut value = |name: &str| args.value(name);
+
+        match flag.as_str() {
+            "--year" | "-y" => options.year = parse_year(&value("--year")?)?,
+            "--day" | "-d" => options.days = Some(parse_days(&value("--day")?)?),
+            "--part" | "-p" => options.config.part = Some(parse_part(&value("--part")?)?),
+            "--input" | "-i" => options.config.input = parse_input(value("--input")?),
+            "--check" => options.config.check = true,
+            "--verbose" | "-v" => options.config.verbose = true,
+            "--param" => parse_param(&value("--param")?, &mut options.config.params)?,
+            "--parallel" => options.parallel = true,
+            "--fetch" => options.fetch = true,
+            "--alloc" => options.alloc = true,
+            "--impl" => options.implementation = parse_implementation(value("--impl")?),
+            "--timeout" | "-t" => {
+                parse_timeout(&value("--timeout")?, &mut options.config.timeouts)?
+            }
+            "--format" | "-f" => options.format = parse_format(&value("--format")?)?,
+            "--bench" | "-b" => options.config.bench = Some(parse_runs(&value("--bench")?)?),
+            "--save-baseline" => options.save_baseline = Some(value("--save-baseline")?.into()),
+            "--compare" => options.compare = Some(value("--compare")?.into()),
+            "--threshold" => options.threshold = parse_threshold(&value("--threshold")?)?,
+            "--help" | "-h" => return Err(String::from(USAGE)),
+            positional if !positional.starts_with('-') => options.year = parse_year(positional)?,
+            unknown => return Err(format!("Unknown argument: {}", unknown)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse_year(s: &str) -> Result<Option<u32>, String> {
+    if s == "all" {
+        return Ok(None);
+    }
+    s.parse()
+        .map(Some)
+        .map_err(|_| format!("Invalid year: {}", s))
+}
+
+fn parse_part(s: &str) -> Result<u32, String> {
+    match s {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("Invalid part: {} (expected 1 or 2)", s)),
+    }
+}
+
+fn parse_format(s: &str) -> Result<Format, String> {
+    match s {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        _ => Err(format!(
+            "Invalid format: {} (expected text, json or csv)",
+            s
+        )),
+    }
+}
+
+fn parse_param(s: &str, params: &mut Params) -> Result<(), String> {
+    let parsed = s
+        .split_once('=')
+        .and_then(|(name, value)| Some((name.trim(), value.trim().parse().ok()?)));
+    match parsed {
+        Some((name, value)) if !name.is_empty() => {
+            params.set(name, value);
+            Ok(())
+        }
+        _ => Err(format!("Invalid parameter: {} (expected NAME=VALUE)", s)),
+    }
+}
+
+fn parse_implementation(s: String) -> Implementation {
+    match s.as_str() {
+        "all" => Implementation::All,
+        DEFAULT_IMPLEMENTATION => Implementation::Default,
+        _ => Implementation::Named(s),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(s: &str, what: &str) -> Result<T, String> {
+    s.parse().map_err(|_| format!("Invalid {}: {}", what, s))
+}
+
+fn parse_runs(s: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(n @ 1..) => Ok(n),
+        _ => Err(format!("Invalid number of runs: {}", s)),
+    }
+}
+
+fn parse_threshold(s: &str) -> Result<f64, String> {
+    match s.trim_end_matches('%').parse() {
+        Ok(t) if t >= 0.0 => Ok(t),
+        _ => Err(format!("Invalid threshold: {}", s)),
+    }
+}
+
+fn parse_timeout(s: &str, timeouts: &mut Timeouts) -> Result<(), String> {
+    let invalid = || format!("Invalid timeout: {}", s);
+    match s.split_once('=') {
+        Some((day, budget)) => {
+            let (year, day) = day.split_once('/').ok_or_else(invalid)?;
+            let year = year.parse().map_err(|_| invalid())?;
+            let day = day.parse().map_err(|_| invalid())?;
+            let budget = parse_duration(budget).ok_or_else(invalid)?;
+            timeouts.days.push(((year, day), budget));
+        }
+        None => timeouts.global = Some(parse_duration(s).ok_or_else(invalid)?),
+    }
+    Ok(())
+}
+
+/// Parses durations like `250ms`, `30s`, `2m` or a bare number of seconds.
+fn parse_duration(s: &str) -> Option<Duration> {
+    let (number, unit) = match s.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let number: f64 = number.parse().ok().filter(|n: &f64| *n > 0.0)?;
+    let seconds = match unit {
+        "ms" => number / 1000.0,
+        "s" => number,
+        "m" => number * 60.0,
+        _ => return None,
+    };
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+fn parse_input(s: String) -> InputSource {
+    match s.as_str() {
+        "-" => InputSource::Stdin,
+        _ => InputSource::File(PathBuf::from(s)),
+    }
+}
+
+fn parse_day(s: &str) -> Result<u32, String> {
+    match s.trim().parse() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => Err(format!("Invalid day: {}", s)),
+    }
+}
+
+/// Parses a comma separated list of days and ranges, e.g. `1,3..=5,9..11`.
+pub fn parse_days(s: &str) -> Result<Vec<u32>, String> {
+    let mut days = Vec::new();
+    for item in s.split(',') {
+        if let Some((start, end)) = item.split_once("..=") {
+            days.extend(parse_day(start)?..=parse_day(end)?);
+        } else if let Some((start, end)) = item.split_once("..") {
+            days.extend(parse_day(start)?..parse_day(end)?);
+        } else {
+            days.push(parse_day(item)?);
+        }
+    }
+
+    days.sort_unstable();
+    days.dedup();
+    if days.is_empty() {
+        return Err(format!("Empty day range: {}", s));
+    }
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn days_single_list_and_range() {
+        assert_eq!(parse_days("3"), Ok(vec![3]));
+        assert_eq!(parse_days("6,1,4"), Ok(vec![1, 4, 6]));
+        assert_eq!(parse_days("3..=7"), Ok(vec![3, 4, 5, 6, 7]));
+        assert_eq!(parse_days("3..5,1,4"), Ok(vec![1, 3, 4]));
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("7..=3").is_err());
+        assert!(parse_days("x").is_err());
+    }
+
+    #[test]
+    fn flags_and_positional_year() {
+        let options = parse_args(args("2015 --day 1..=2 --part=2")).unwrap();
+        assert_eq!(options.year, Some(2015));
+        assert_eq!(options.days, Some(vec![1, 2]));
+        assert_eq!(options.config.part, Some(2));
+        assert_eq!(options.config.input, InputSource::Default);
+
+        let options = parse_args(args("-d 5 --input -")).unwrap();
+        assert_eq!(options.config.input, InputSource::Stdin);
+
+        let options = parse_args(args("--timeout 2s -t 2015/4=1.5m")).unwrap();
+        let timeouts = &options.config.timeouts;
+        assert_eq!(timeouts.for_day(2015, 4), Some(Duration::from_secs(90)));
+        assert_eq!(timeouts.for_day(2022, 4), Some(Duration::from_secs(2)));
+        assert!(parse_args(args("--timeout 5h")).is_err());
+        assert!(parse_args(args("--timeout 4=5s")).is_err());
+
+        let options = parse_args(args("--year all")).unwrap();
+        assert_eq!(options.year, None);
+        assert!(options.includes_day(25));
+
+        let options = parse_args(args("--param rounds=500 --param=relief=1")).unwrap();
+        assert_eq!(
+            options.config.params,
+            Params::default().with("rounds", 500).with("relief", 1)
+        );
+        assert!(parse_args(args("--param rounds")).is_err());
+        assert!(parse_args(args("--param =5")).is_err());
+
+        let options = parse_args(args("--impl bitmask")).unwrap();
+        assert_eq!(
+            options.implementation,
+            Implementation::Named(String::from("bitmask"))
+        );
+        let options = parse_args(args("--impl=all")).unwrap();
+        assert_eq!(options.implementation, Implementation::All);
+
+        assert!(parse_args(args("--part 3")).is_err());
+        assert!(parse_args(args("--day")).is_err());
+        assert!(parse_args(args("--bogus")).is_err());
+    }
+
+    #[test]
+    fn new_command() {
+        let Ok(Command::New { year, day }) = parse_command(args("new --year=2023 -d 7")) else {
+            panic!("expected the new command");
+        };
+        assert_eq!((year, day), (2023, 7));
+        assert!(parse_command(args("new --day 7")).is_err());
+        assert!(matches!(parse_command(args("2015")), Ok(Command::Run(_))));
+
+        let Ok(Command::Fetch(options)) = parse_command(args("fetch 2015 --day=1..3")) else {
+            panic!("expected the fetch command");
+        };
+        assert_eq!(options.year, Some(2015));
+        assert_eq!(options.days, Some(vec![1, 2]));
+        assert!(parse_command(args("fetch --check")).is_err());
+
+        let Ok(Command::Submit { part, answer, .. }) =
+            parse_command(args("submit -y 2022 -d 11 -p 2 --answer=42"))
+        else {
+            panic!("expected the submit command");
+        };
+        assert_eq!((part, answer.as_deref()), (2, Some("42")));
+        assert!(parse_command(args("submit -y 2022 -d 11")).is_err());
+
+        let Ok(Command::Generate {
+            size, seed, output, ..
+        }) = parse_command(args("generate -y 2022 -d 7 --size 5000 -o big"))
+        else {
+            panic!("expected the generate command");
+        };
+        assert_eq!((size, seed), (5000, 0));
+        assert_eq!(output, Some(PathBuf::from("big")));
+        assert!(parse_command(args("generate -y 2022 -d 7 --size -1")).is_err());
+        assert!(parse_command(args("generate -y 2022 -d 7")).is_err());
+    }
+}
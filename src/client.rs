@@ -0,0 +1,296 @@
+use serde::Deserialize;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Where to reach the puzzle site and how to authenticate, read from
+/// `aoc.toml` and overridden by the `AOC_SESSION` and `AOC_BASE_URL`
+/// environment variables:
+///
+/// ```toml
+/// session = "53616c7465645f5f..."
+/// base_url = "http://localhost:8080"
+/// ```
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ClientConfig {
+    /// Value of the site's `session` cookie.
+    pub session: Option<String>,
+    pub base_url: String,
+    /// Minimum time between two requests.
+    #[serde(with = "seconds")]
+    pub min_interval: Duration,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            session: None,
+            base_url: String::from(DEFAULT_BASE_URL),
+            min_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+mod seconds {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer};
+    use std::time::Duration;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+        Duration::try_from_secs_f64(f64::deserialize(d)?).map_err(D::Error::custom)
+    }
+}
+
+impl ClientConfig {
+    pub const PATH: &'static str = "aoc.toml";
+
+    /// Loads the config file, if there is one, and applies the environment.
+    pub fn load() -> Result<Self, String> {
+        let path = Path::new(Self::PATH);
+        let mut config = match std::fs::read_to_string(path) {
+            Ok(raw) => toml::from_str(&raw).map_err(|e| format!("{}: {}", path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        };
+        if let Ok(session) = std::env::var("AOC_SESSION") {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = std::env::var("AOC_BASE_URL") {
+            config.base_url = base_url;
+        }
+        Ok(config)
+    }
+}
+
+/// HTTP client for the puzzle site that waits `min_interval` between
+/// requests.
+pub struct Client {
+    agent: ureq::Agent,
+    config: ClientConfig,
+    last_request: Option<Instant>,
+}
+
+impl Client {
+    pub fn new(config: ClientConfig) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .build();
+        Self {
+            agent,
+            config,
+            last_request: None,
+        }
+    }
+
+    /// Sends a GET request for `path`, relative to the base URL, and returns
+    /// the response body.
+    pub fn get(&mut self, path: &str) -> Result<String, String> {
+        let url = self.url(path);
+        let request = self.request("GET", &url)?;
+        Self::body(&url, request.call())
+    }
+
+    /// Sends `fields` as a form to `path` and returns the response body.
+    pub fn post_form(&mut self, path: &str, fields: &[(&str, &str)]) -> Result<String, String> {
+        let url = self.url(path);
+        let request = self.request("POST", &url)?;
+        Self::body(&url, request.send_form(fields))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.config.base_url.trim_end_matches('/'), path)
+    }
+
+    /// Builds an authenticated request, first waiting out the rate limit.
+    fn request(&mut self, method: &str, url: &str) -> Result<ureq::Request, String> {
+        let session = self.config.session.as_ref().ok_or_else(|| {
+            format!(
+                "No session token; set AOC_SESSION or `session` in {}",
+                ClientConfig::PATH
+            )
+        })?;
+
+        if let Some(last) = self.last_request {
+            thread::sleep(self.config.min_interval.saturating_sub(last.elapsed()));
+        }
+        self.last_request = Some(Instant::now());
+
+        Ok(self
+            .agent
+            .request(method, url)
+            .set("Cookie", &format!("session={}", session)))
+    }
+
+    fn body(url: &str, response: Result<ureq::Response, ureq::Error>) -> Result<String, String> {
+        match response {
+            Ok(response) => response
+                .into_string()
+                .map_err(|e| format!("{}: {}", url, e)),
+            Err(ureq::Error::Status(code, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                let detail = body.lines().next().unwrap_or_default();
+                Err(format!("{}: HTTP {} {}", url, code, detail.trim()))
+            }
+            Err(e) => Err(format!("{}: {}", url, e)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already cached; no request was made.
+    Cached,
+    Downloaded,
+}
+
+/// Downloads the input of `year` and `day` to `path` unless it is already
+/// there.
+pub fn fetch_input(
+    client: &mut Client,
+    year: u32,
+    day: u32,
+    path: &Path,
+) -> Result<Fetched, String> {
+    if path.exists() {
+        return Ok(Fetched::Cached);
+    }
+
+    let input = client.get(&format!("/{}/day/{}/input", year, day))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    std::fs::write(path, input).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(Fetched::Downloaded)
+}
+
+/// A stand-in for the puzzle site that answers each request in turn with a
+/// canned response and hands back what it received.
+#[cfg(test)]
+pub mod mock {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    pub struct Request {
+        pub request_line: String,
+        pub cookie: Option<String>,
+        pub body: String,
+    }
+
+    /// Serves `responses` as `(status, body)` pairs, one per connection, and
+    /// returns the base URL and a handle yielding the received requests.
+    pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<Request>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+
+                let (mut cookie, mut length) = (None, 0);
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    let header = header.trim_end();
+                    if header.is_empty() {
+                        break;
+                    }
+                    let (name, value) = header.split_once(": ").unwrap();
+                    match name.to_ascii_lowercase().as_str() {
+                        "cookie" => cookie = Some(value.to_string()),
+                        "content-length" => length = value.parse().unwrap(),
+                        _ => {}
+                    }
+                }
+                let mut request_body = vec![0; length];
+                reader.read_exact(&mut request_body).unwrap();
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+                requests.push(Request {
+                    request_line: request_line.trim_end().to_string(),
+                    cookie,
+                    body: String::from_utf8(request_body).unwrap(),
+                });
+            }
+            requests
+        });
+        (base_url, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(base_url: String, min_interval: Duration) -> Client {
+        Client::new(ClientConfig {
+            session: Some(String::from("abc")),
+            base_url,
+            min_interval,
+        })
+    }
+
+    #[test]
+    fn fetches_once_and_caches() {
+        let (base_url, server) = mock::serve(vec![(200, "1\n2\n3\n"), (404, "Not found\n")]);
+        let mut client = client(base_url, Duration::from_millis(50));
+        let dir = std::env::temp_dir().join(format!("aoc-fetch-{}", std::process::id()));
+        let path = dir.join("2022/day01");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let start = Instant::now();
+        assert_eq!(
+            fetch_input(&mut client, 2022, 1, &path),
+            Ok(Fetched::Downloaded)
+        );
+        assert_eq!(
+            fetch_input(&mut client, 2022, 1, &path),
+            Ok(Fetched::Cached)
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+
+        let missing = fetch_input(&mut client, 2022, 25, &dir.join("2022/day25"));
+        assert!(missing
+            .unwrap_err()
+            .ends_with("/2022/day/25/input: HTTP 404 Not found"));
+        // The second request waited for the interval after the first.
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests[0].request_line, "GET /2022/day/1/input HTTP/1.1");
+        assert_eq!(requests[0].cookie.as_deref(), Some("session=abc"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn config_file() {
+        let config: ClientConfig = toml::from_str("session = \"abc\"\nmin_interval = 2.5").unwrap();
+        assert_eq!(config.session.as_deref(), Some("abc"));
+        assert_eq!(config.base_url, DEFAULT_BASE_URL);
+        assert_eq!(config.min_interval, Duration::from_millis(2500));
+
+        for bad in ["-1", "nan", "1e30"] {
+            let raw = format!("min_interval = {}", bad);
+            assert!(toml::from_str::<ClientConfig>(&raw).is_err(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use crate::common::bfs::bfs;
+use crate::common::dijkstra::{shortest_paths, Edge, Graph};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// The cost of the cheapest path between every two nodes of an adjacency
+/// list, by row of the node it starts from, `None` where there is no path.
+/// Takes cubic time in the number of nodes whatever the number of edges, so
+/// suits small or dense graphs.
+pub fn floyd_warshall(graph: &[Vec<Edge>]) -> Vec<Vec<Option<usize>>> {
+    let n = graph.len();
+    let mut dist = vec![vec![None; n]; n];
+    for (from, row) in dist.iter_mut().enumerate() {
+        row[from] = Some(0);
+        for (to, cost) in graph.neighbors(from) {
+            if row[to].is_none_or(|c| cost < c) {
+                row[to] = Some(cost);
+            }
+        }
+    }
+
+    for via in 0..n {
+        // Costs are never negative, so going through `via` cannot make the
+        // ways on from it any cheaper.
+        let onward = dist[via].clone();
+        for row in dist.iter_mut() {
+            let Some(first) = row[via] else {
+                continue;
+            };
+            for (cell, second) in row.iter_mut().zip(&onward) {
+                if let Some(second) = second {
+                    if cell.is_none_or(|c| first + second < c) {
+                        *cell = Some(first + second);
+                    }
+                }
+            }
+        }
+    }
+    dist
+}
+
+/// The cost of the cheapest path between every two of `points`, by row of
+/// the point it starts from, with one Dijkstra search from each. Suits a
+/// few points in a large or implicit graph.
+pub fn point_distances<G>(graph: &G, points: &[G::Node]) -> Vec<Vec<Option<G::Cost>>>
+where
+    G: Graph + ?Sized,
+{
+    points
+        .iter()
+        .map(|from| {
+            let paths = shortest_paths(graph, from.clone());
+            points.iter().map(|to| paths.cost(to)).collect()
+        })
+        .collect()
+}
+
+/// Like [`point_distances`], in steps to the nodes `neighbors` leads to, with
+/// one breadth-first search from each point.
+pub fn point_steps<N, F, I>(points: &[N], mut neighbors: F) -> Vec<Vec<Option<usize>>>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    points
+        .iter()
+        .map(|from| {
+            let reached = bfs(from.clone(), &mut neighbors);
+            points.iter().map(|to| reached.distance(to)).collect()
+        })
+        .collect()
+}
+
+/// Reduces `graph` to an adjacency list over just `points`, indexed like
+/// them, with an edge wherever one point reaches another without passing
+/// through a third, costing the cheapest such path. Paths through other
+/// points are left to the searches over the result, so its shortest paths
+/// are those of `graph`.
+pub fn compress<G>(graph: &G, points: &[G::Node]) -> Vec<Vec<Edge>>
+where
+    G: Graph<Cost = usize> + ?Sized,
+{
+    let stops: HashSet<_> = points.iter().cloned().collect();
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, from)| {
+            let paths = shortest_paths(
+                &StopAt {
+                    graph,
+                    stops: &stops,
+                    from,
+                },
+                from.clone(),
+            );
+            points
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .filter_map(|(j, to)| Some(Edge::new(j, paths.cost(to)?)))
+                .collect()
+        })
+        .collect()
+}
+
+/// `graph` with the way on through `stops` closed, except from `from`.
+struct StopAt<'a, G: Graph + ?Sized> {
+    graph: &'a G,
+    stops: &'a HashSet<G::Node>,
+    from: &'a G::Node,
+}
+
+impl<G: Graph + ?Sized> Graph for StopAt<'_, G> {
+    type Node = G::Node;
+    type Cost = G::Cost;
+
+    fn neighbors(&self, node: G::Node) -> impl Iterator<Item = (G::Node, G::Cost)> {
+        let open = node == *self.from || !self.stops.contains(&node);
+        self.graph.neighbors(node).filter(move |_| open)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A ring 0 -> 1 -> ... -> 5 -> 0 costing one per edge, with a dear
+    /// shortcut 0 -> 3 and a spur 2 -> 6 that leads nowhere.
+    fn graph() -> Vec<Vec<Edge>> {
+        let mut graph: Vec<_> = (0..6).map(|n| vec![Edge::new((n + 1) % 6, 1)]).collect();
+        graph[0].push(Edge::new(3, 5));
+        graph[2].push(Edge::new(6, 4));
+        graph.push(vec![]);
+        graph
+    }
+
+    #[test]
+    fn all_pairs_agree() {
+        let graph = graph();
+        let dist = floyd_warshall(&graph);
+        assert_eq!(dist[0][3], Some(3));
+        assert_eq!(dist[3][0], Some(3));
+        assert_eq!(dist[4][6], Some(8));
+        assert_eq!(dist[6][0], None);
+        assert_eq!(dist[6][6], Some(0));
+
+        let nodes: Vec<_> = (0..graph.len()).collect();
+        assert_eq!(point_distances(&graph, &nodes), dist);
+        let steps = point_steps(&nodes, |&n| graph.neighbors(n).map(|(next, _)| next));
+        assert_eq!(steps[0][3], Some(1));
+        assert_eq!(steps[4][6], Some(5));
+    }
+
+    #[test]
+    fn compressed_keeps_distances() {
+        let graph = graph();
+        let points = [0, 2, 4, 6];
+        let compressed = compress(&graph, &points);
+        let edges: Vec<Vec<_>> = (0..points.len())
+            .map(|n| compressed.neighbors(n).collect())
+            .collect();
+        // 0 reaches 4 through 3 by the shortcut, but 2 is on the way round.
+        assert_eq!(
+            edges,
+            [
+                vec![(1, 2), (2, 6)],
+                vec![(2, 2), (3, 4)],
+                vec![(0, 2)],
+                vec![]
+            ]
+        );
+        assert_eq!(
+            floyd_warshall(&compressed),
+            point_distances(&graph, &points)
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// The nodes a breadth-first search reached, each with its distance in steps
+/// from the nearest start and the node it was first reached from.
+pub struct Reached<N> {
+    reached: HashMap<N, (usize, Option<N>)>,
+    /// The goal the search stopped at, for [`bfs_matching`].
+    pub goal: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash> Reached<N> {
+    /// `None` if `node` was not reached.
+    pub fn distance(&self, node: &N) -> Option<usize> {
+        self.reached.get(node).map(|(distance, _)| *distance)
+    }
+
+    /// The node `node` was reached from, `None` for the starts and the nodes
+    /// not reached.
+    pub fn parent(&self, node: &N) -> Option<&N> {
+        self.reached.get(node)?.1.as_ref()
+    }
+
+    /// The nodes from a start to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.distance(node)?;
+        let mut nodes = vec![node.clone()];
+        while let Some(parent) = self.parent(nodes.last().unwrap()) {
+            nodes.push(parent.clone());
+        }
+        nodes.reverse();
+        Some(nodes)
+    }
+
+    /// Every reached node with its distance.
+    pub fn distances(&self) -> impl Iterator<Item = (&N, usize)> {
+        self.reached
+            .iter()
+            .map(|(node, (distance, _))| (node, *distance))
+    }
+}
+
+/// Searches breadth first from `start` over the nodes `neighbors` leads to,
+/// each one step away.
+pub fn bfs<N, F, I>(start: N, neighbors: F) -> Reached<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    bfs_from([start], neighbors)
+}
+
+/// Like [`bfs`], from all of `starts` at once, so that each node gets its
+/// distance from the nearest.
+pub fn bfs_from<N, S, F, I>(starts: S, neighbors: F) -> Reached<N>
+where
+    N: Clone + Eq + Hash,
+    S: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    bfs_matching(starts, neighbors, |_| false)
+}
+
+/// Like [`bfs_from`], stopping at the first node matching `is_goal`, which is
+/// one of the nearest to the starts.
+pub fn bfs_matching<N, S, F, I, G>(starts: S, mut neighbors: F, mut is_goal: G) -> Reached<N>
+where
+    N: Clone + Eq + Hash,
+    S: IntoIterator<Item = N>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let mut reached = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Entry::Vacant(e) = reached.entry(start.clone()) {
+            e.insert((0, None));
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((node, distance)) = queue.pop_front() {
+        if is_goal(&node) {
+            return Reached {
+                reached,
+                goal: Some(node),
+            };
+        }
+
+        for next in neighbors(&node) {
+            if let Entry::Vacant(e) = reached.entry(next.clone()) {
+                e.insert((distance + 1, Some(node.clone())));
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    Reached {
+        reached,
+        goal: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The open squares of a small maze, as (row, col).
+    fn maze() -> impl FnMut(&(usize, usize)) -> Vec<(usize, usize)> {
+        let rows = ["..#...", ".##.#.", "....#.", "#.#...", "..#.#."];
+        move |&(row, col)| {
+            [(0, 1), (1, 0), (0, -1), (-1, 0)]
+                .into_iter()
+                .filter_map(|(dr, dc)| {
+                    let row = row.checked_add_signed(dr)?;
+                    let col = col.checked_add_signed(dc)?;
+                    let open = rows.get(row)?.as_bytes().get(col)? == &b'.';
+                    open.then_some((row, col))
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn single_source() {
+        let reached = bfs((0, 0), maze());
+        assert_eq!(reached.distance(&(0, 0)), Some(0));
+        assert_eq!(reached.distance(&(0, 5)), Some(9));
+        assert_eq!(reached.distance(&(4, 3)), Some(7));
+        assert_eq!(reached.distance(&(4, 0)), Some(6));
+        assert_eq!(reached.distance(&(0, 2)), None);
+        assert_eq!(reached.distances().count(), 21);
+        assert_eq!(reached.goal, None);
+
+        let path = reached.path_to(&(4, 3)).unwrap();
+        assert_eq!(path.len(), 8);
+        assert_eq!((path[0], path[7]), ((0, 0), (4, 3)));
+        assert_eq!(reached.parent(&(4, 3)), Some(&path[6]));
+        assert_eq!(reached.parent(&(0, 0)), None);
+        assert_eq!(reached.path_to(&(0, 2)), None);
+    }
+
+    #[test]
+    fn multi_source_and_goal() {
+        let reached = bfs_from([(0, 0), (0, 5)], maze());
+        assert_eq!(reached.distance(&(4, 3)), Some(6));
+        assert_eq!(reached.distance(&(2, 3)), Some(4));
+        assert_eq!(reached.path_to(&(1, 5)), Some(vec![(0, 5), (1, 5)]));
+
+        let reached = bfs_matching([(0, 0), (0, 5)], maze(), |&(row, _)| row == 4);
+        assert_eq!(reached.goal, Some((4, 5)));
+        assert_eq!(reached.distance(&(4, 5)), Some(4));
+        assert_eq!(reached.path_to(&(4, 5)).unwrap()[0], (0, 5));
+
+        let reached = bfs_matching([(0, 0)], maze(), |&node| node == (0, 2));
+        assert_eq!(reached.goal, None);
+    }
+}
@@ -1,113 +1,453 @@
 use std::cmp::Ordering;
-use std::collections::BinaryHeap;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::ops::{Add, Sub};
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct State {
+/// Costs of edges and paths: added up along a path and compared, starting
+/// from zero.
+pub trait Cost: Copy + Ord + Add<Output = Self> {
+    const ZERO: Self;
+}
+
+macro_rules! impl_cost {
+    ($($t:ty),*) => {
+        $(impl Cost for $t {
+            const ZERO: Self = 0;
+        })*
+    };
+}
+
+impl_cost!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// A directed graph given by the edges leaving each node, so that it can
+/// be implicit, such as the positions of a grid or the states of a game,
+/// rather than built up front.
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+    type Cost: Cost;
+
+    /// The nodes reachable from `node` in one step, with the step's cost.
+    fn neighbors(&self, node: Self::Node) -> impl Iterator<Item = (Self::Node, Self::Cost)>;
+}
+
+pub struct Edge {
+    node: usize,
     cost: usize,
-    position: usize,
 }
 
-impl Ord for State {
+impl Edge {
+    pub fn new(node: usize, cost: usize) -> Self {
+        Self { node, cost }
+    }
+}
+
+/// An adjacency list, indexed by node.
+impl Graph for [Vec<Edge>] {
+    type Node = usize;
+    type Cost = usize;
+
+    fn neighbors(&self, node: usize) -> impl Iterator<Item = (usize, usize)> {
+        self[node].iter().map(|edge| (edge.node, edge.cost))
+    }
+}
+
+impl Graph for Vec<Vec<Edge>> {
+    type Node = usize;
+    type Cost = usize;
+
+    fn neighbors(&self, node: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.as_slice().neighbors(node)
+    }
+}
+
+/// A node waiting in the queue, ordered so that the lowest estimate of a
+/// whole path through it comes out of the max-heap first. Among equal
+/// estimates the furthest node goes first, as it is likely nearer the goal.
+struct State<N, C> {
+    estimate: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> Ord for State<N, C> {
     fn cmp(&self, other: &Self) -> Ordering {
         other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.position.cmp(&other.position))
+            .estimate
+            .cmp(&self.estimate)
+            .then(self.cost.cmp(&other.cost))
     }
 }
 
-impl PartialOrd for State {
+impl<N, C: Ord> PartialOrd for State<N, C> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-pub struct Edge {
-    node: usize,
-    cost: usize,
+impl<N, C: Ord> PartialEq for State<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
 }
 
-impl Edge {
-    pub fn new(node: usize, cost: usize) -> Self {
-        Self { node, cost }
+impl<N, C: Ord> Eq for State<N, C> {}
+
+/// A cheapest path and its cost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N = usize, C = usize> {
+    pub cost: C,
+    /// From the start to the goal, both included.
+    pub nodes: Vec<N>,
+}
+
+/// Costs of the cheapest paths from a start node, with the predecessor of
+/// each reached node on its path to tell the paths themselves.
+pub struct ShortestPaths<N = usize, C = usize> {
+    /// Cost and predecessor of each reached node.
+    reached: HashMap<N, (C, Option<N>)>,
+}
+
+impl<N: Clone + Eq + Hash, C: Cost> ShortestPaths<N, C> {
+    /// `None` if `node` cannot be reached.
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.reached.get(node).map(|(cost, _)| *cost)
+    }
+
+    /// The nodes from the start to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Path<N, C>> {
+        let cost = self.cost(node)?;
+        let mut nodes = vec![node.clone()];
+        while let Some((_, Some(previous))) = self.reached.get(nodes.last().unwrap()) {
+            nodes.push(previous.clone());
+        }
+        nodes.reverse();
+        Some(Path { cost, nodes })
+    }
+
+    /// Every reached node with the cost of getting there.
+    pub fn costs(&self) -> impl Iterator<Item = (&N, C)> {
+        self.reached.iter().map(|(node, (cost, _))| (node, *cost))
     }
 }
 
-pub fn shortest_path(adj_list: &Vec<Vec<Edge>>, start: usize, goal: usize) -> Option<usize> {
-    let mut dist: Vec<_> = (0..adj_list.len()).map(|_| usize::MAX).collect();
+/// The [`ShortestPaths`] between the nodes of `G`.
+pub type PathsIn<G> = ShortestPaths<<G as Graph>::Node, <G as Graph>::Cost>;
+
+/// What a search found: the goal it stopped at, if any, and the paths it
+/// took to the nodes it reached on the way.
+struct Explored<N, C> {
+    paths: ShortestPaths<N, C>,
+    goal: Option<N>,
+    expanded: usize,
+}
+
+/// Runs A* from `start` until it settles a node matching `is_goal`, going
+/// by `heuristic`'s estimates of the cost left from each node. With a zero
+/// heuristic this is Dijkstra's algorithm. Costs are only final for settled
+/// nodes.
+fn search<G, F, H>(
+    graph: &G,
+    start: G::Node,
+    mut is_goal: F,
+    mut heuristic: H,
+) -> Explored<G::Node, G::Cost>
+where
+    G: Graph + ?Sized,
+    F: FnMut(&G::Node) -> bool,
+    H: FnMut(&G::Node) -> G::Cost,
+{
+    let mut paths = ShortestPaths {
+        reached: HashMap::new(),
+    };
+    let mut expanded = 0;
 
     let mut heap = BinaryHeap::new();
 
-    dist[start] = 0;
+    paths.reached.insert(start.clone(), (G::Cost::ZERO, None));
     heap.push(State {
-        cost: 0,
-        position: start,
+        estimate: heuristic(&start),
+        cost: G::Cost::ZERO,
+        node: start,
     });
 
-    while let Some(State { cost, position }) = heap.pop() {
-        if position == goal {
-            return Some(cost);
+    while let Some(State { cost, node, .. }) = heap.pop() {
+        if is_goal(&node) {
+            return Explored {
+                paths,
+                goal: Some(node),
+                expanded,
+            };
         }
 
-        if cost > dist[position] {
+        if cost > paths.reached[&node].0 {
             continue;
         }
+        expanded += 1;
 
-        for edge in &adj_list[position] {
+        for (next, step) in graph.neighbors(node.clone()) {
+            let cost = cost + step;
             let next = State {
-                cost: cost + edge.cost,
-                position: edge.node,
+                estimate: cost + heuristic(&next),
+                cost,
+                node: next,
             };
 
-            if next.cost < dist[next.position] {
+            let improved = match paths.reached.entry(next.node.clone()) {
+                Entry::Occupied(mut e) if next.cost < e.get().0 => {
+                    e.insert((next.cost, Some(node.clone())));
+                    true
+                }
+                Entry::Occupied(_) => false,
+                Entry::Vacant(e) => {
+                    e.insert((next.cost, Some(node.clone())));
+                    true
+                }
+            };
+            if improved {
                 heap.push(next);
-                dist[next.position] = next.cost;
             }
         }
     }
 
-    None
+    Explored {
+        paths,
+        goal: None,
+        expanded,
+    }
+}
+
+fn no_heuristic<N, C: Cost>(_: &N) -> C {
+    C::ZERO
+}
+
+pub fn shortest_path<G>(graph: &G, start: G::Node, goal: G::Node) -> Option<G::Cost>
+where
+    G: Graph + ?Sized,
+{
+    shortest_path_matching(graph, start, |node| *node == goal)
+}
+
+pub fn shortest_path_matching<G, F>(graph: &G, start: G::Node, is_goal: F) -> Option<G::Cost>
+where
+    G: Graph + ?Sized,
+    F: FnMut(&G::Node) -> bool,
+{
+    let explored = search(graph, start, is_goal, no_heuristic);
+    explored.paths.cost(&explored.goal?)
+}
+
+/// Like [`shortest_path`], also returning the path taken.
+pub fn shortest_route<G>(graph: &G, start: G::Node, goal: G::Node) -> Option<Path<G::Node, G::Cost>>
+where
+    G: Graph + ?Sized,
+{
+    shortest_route_matching(graph, start, |node| *node == goal)
 }
 
-pub fn shortest_path_matching<F>(
-    adj_list: &Vec<Vec<Edge>>,
-    start: usize,
+/// Like [`shortest_path_matching`], also returning the path taken.
+pub fn shortest_route_matching<G, F>(
+    graph: &G,
+    start: G::Node,
     is_goal: F,
-) -> Option<usize>
+) -> Option<Path<G::Node, G::Cost>>
 where
-    F: Fn(usize) -> bool,
+    G: Graph + ?Sized,
+    F: FnMut(&G::Node) -> bool,
 {
-    let mut dist: Vec<_> = (0..adj_list.len()).map(|_| usize::MAX).collect();
+    let explored = search(graph, start, is_goal, no_heuristic);
+    explored.paths.path_to(&explored.goal?)
+}
 
-    let mut heap = BinaryHeap::new();
+/// The cheapest paths from `start` to every node it can reach.
+pub fn shortest_paths<G>(graph: &G, start: G::Node) -> PathsIn<G>
+where
+    G: Graph + ?Sized,
+{
+    search(graph, start, |_| false, no_heuristic).paths
+}
 
-    dist[start] = 0;
-    heap.push(State {
-        cost: 0,
-        position: start,
-    });
+/// How an [`a_star`] search went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Search<N = usize, C = usize> {
+    /// `None` if the goal cannot be reached.
+    pub path: Option<Path<N, C>>,
+    /// Nodes whose neighbors were looked at, which a better heuristic keeps
+    /// down. A heuristic of zero expands what [`shortest_route`] would.
+    pub expanded: usize,
+}
 
-    while let Some(State { cost, position }) = heap.pop() {
-        if is_goal(position) {
-            return Some(cost);
-        }
+/// A cheapest path from `start` to `goal`, searched for first where
+/// `heuristic` expects the goal to be closest. The heuristic must never
+/// overestimate the cost left from a node, or a dearer path may be found.
+pub fn a_star<G, H>(graph: &G, start: G::Node, goal: G::Node, heuristic: H) -> SearchIn<G>
+where
+    G: Graph + ?Sized,
+    H: FnMut(&G::Node) -> G::Cost,
+{
+    a_star_matching(graph, start, |node| *node == goal, heuristic)
+}
 
-        if cost > dist[position] {
-            continue;
+/// Like [`a_star`], stopping at the first node matching `is_goal`. The
+/// heuristic must never overestimate the cost to the nearest such node.
+pub fn a_star_matching<G, F, H>(graph: &G, start: G::Node, is_goal: F, heuristic: H) -> SearchIn<G>
+where
+    G: Graph + ?Sized,
+    F: FnMut(&G::Node) -> bool,
+    H: FnMut(&G::Node) -> G::Cost,
+{
+    let explored = search(graph, start, is_goal, heuristic);
+    Search {
+        path: explored.goal.and_then(|goal| explored.paths.path_to(&goal)),
+        expanded: explored.expanded,
+    }
+}
+
+/// The [`Search`] between the nodes of `G`.
+pub type SearchIn<G> = Search<<G as Graph>::Node, <G as Graph>::Cost>;
+
+/// The distance between two grid positions moving only along the axes, a
+/// heuristic for grids where each such step costs at least one.
+pub fn manhattan<T>(a: (T, T), b: (T, T)) -> T
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T>,
+{
+    distance(a.0, b.0) + distance(a.1, b.1)
+}
+
+/// The distance between two grid positions also moving diagonally, a
+/// heuristic for grids where each step to any of the eight neighbors costs
+/// at least one.
+pub fn chebyshev<T>(a: (T, T), b: (T, T)) -> T
+where
+    T: Copy + Ord + Sub<Output = T>,
+{
+    distance(a.0, b.0).max(distance(a.1, b.1))
+}
+
+/// `|a - b|`, without going below zero for unsigned types.
+fn distance<T: Copy + Ord + Sub<Output = T>>(a: T, b: T) -> T {
+    a.max(b) - a.min(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0 -> 1 -> 3 costs 2 + 2, cheaper than 0 -> 3 directly or via 2.
+    fn graph() -> Vec<Vec<Edge>> {
+        vec![
+            vec![Edge::new(1, 2), Edge::new(2, 1), Edge::new(3, 5)],
+            vec![Edge::new(3, 2)],
+            vec![Edge::new(3, 4)],
+            vec![],
+            vec![Edge::new(0, 1)],
+        ]
+    }
+
+    #[test]
+    fn routes_and_distances() {
+        let graph = graph();
+        assert_eq!(shortest_path(&graph, 0, 3), Some(4));
+        assert_eq!(
+            shortest_route(&graph, 0, 3),
+            Some(Path {
+                cost: 4,
+                nodes: vec![0, 1, 3]
+            })
+        );
+        assert_eq!(
+            shortest_route_matching(&graph, 0, |n| *n >= 2).map(|p| p.nodes),
+            Some(vec![0, 2])
+        );
+        assert_eq!(shortest_route(&graph, 0, 4), None);
+
+        let paths = shortest_paths(&graph, 0);
+        let costs: Vec<_> = (0..5).map(|n| paths.cost(&n)).collect();
+        assert_eq!(costs, [Some(0), Some(2), Some(1), Some(4), None]);
+        assert_eq!(paths.path_to(&0).map(|p| p.nodes), Some(vec![0]));
+        assert_eq!(paths.path_to(&4), None);
+    }
+
+    /// Numbers reached from 1 by doubling, for 1, or adding 3, for 2.
+    struct Numbers;
+
+    impl Graph for Numbers {
+        type Node = u64;
+        type Cost = u32;
+
+        fn neighbors(&self, n: u64) -> impl Iterator<Item = (u64, u32)> {
+            [(n * 2, 1), (n + 3, 2)].into_iter()
         }
+    }
 
-        for edge in &adj_list[position] {
-            let next = State {
-                cost: cost + edge.cost,
-                position: edge.node,
-            };
+    #[test]
+    fn implicit_graph() {
+        // 1 -> 2 -> 5 -> 10 costs 1 + 2 + 1, less than going through 7.
+        assert_eq!(
+            shortest_route(&Numbers, 1, 10),
+            Some(Path {
+                cost: 4,
+                nodes: vec![1, 2, 5, 10]
+            })
+        );
+        assert_eq!(shortest_path_matching(&Numbers, 5, |n| n % 7 == 0), Some(6));
+    }
 
-            if next.cost < dist[next.position] {
-                heap.push(next);
-                dist[next.position] = next.cost;
-            }
+    /// An open `size` by `size` grid with steps to the four or, if
+    /// `diagonal`, eight neighbors.
+    struct Grid {
+        size: i32,
+        diagonal: bool,
+    }
+
+    impl Graph for Grid {
+        type Node = (i32, i32);
+        type Cost = i32;
+
+        fn neighbors(&self, (x, y): (i32, i32)) -> impl Iterator<Item = ((i32, i32), i32)> {
+            let size = self.size;
+            let diagonal = self.diagonal;
+            (-1..=1)
+                .flat_map(move |dx| (-1..=1).map(move |dy| (dx, dy)))
+                .filter(move |&(dx, dy)| (dx, dy) != (0, 0) && (diagonal || dx == 0 || dy == 0))
+                .map(move |(dx, dy)| (x + dx, y + dy))
+                .filter(move |&(x, y)| (0..size).contains(&x) && (0..size).contains(&y))
+                .map(|node| (node, 1))
         }
     }
 
-    None
+    #[test]
+    fn a_star_on_grids() {
+        assert_eq!(manhattan((1, 5), (4, 1)), 7);
+        assert_eq!(chebyshev((1, 5), (4, 1)), 4);
+        assert_eq!(manhattan((1usize, 5), (4, 1)), 7);
+
+        for (diagonal, heuristic) in [
+            (false, manhattan as fn(_, _) -> _),
+            (true, chebyshev as fn(_, _) -> _),
+        ] {
+            let grid = Grid { size: 20, diagonal };
+            let (start, goal) = ((2, 3), (15, 9));
+            let dijkstra = a_star(&grid, start, goal, |_| 0);
+            let a_star = a_star(&grid, start, goal, |&node| heuristic(node, goal));
+
+            let cost = dijkstra.path.as_ref().unwrap().cost;
+            assert_eq!(cost, if diagonal { 13 } else { 19 });
+            assert_eq!(shortest_path(&grid, start, goal), Some(cost));
+            let path = a_star.path.unwrap();
+            assert_eq!(path.cost, cost);
+            assert_eq!(path.nodes.len() as i32, cost + 1);
+            // Straight along the path, against most of the grid.
+            assert_eq!(a_star.expanded, cost as usize);
+            assert!(dijkstra.expanded > 250);
+        }
+
+        let grid = Grid {
+            size: 5,
+            diagonal: false,
+        };
+        let search = a_star(&grid, (0, 0), (5, 5), |_| 0);
+        assert_eq!((search.path, search.expanded), (None, 25));
+    }
 }
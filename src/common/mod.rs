@@ -1 +1,4 @@
+pub mod all_pairs;
+pub mod bfs;
 pub mod dijkstra;
+pub mod ocr;
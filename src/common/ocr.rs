@@ -0,0 +1,202 @@
+use std::fmt::{self, Display};
+
+/// The 4x6 block letters, with one blank column between letters.
+const SMALL: (usize, usize, &[(char, &str)]) = (
+    4,
+    5,
+    &[
+        ('A', ".##.#..##..######..##..#"),
+        ('B', "###.#..####.#..##..####."),
+        ('C', ".##.#..##...#...#..#.##."),
+        ('E', "#####...###.#...#...####"),
+        ('F', "#####...###.#...#...#..."),
+        ('G', ".##.#..##...#.###..#.###"),
+        ('H', "#..##..######..##..##..#"),
+        ('I', ".###..#...#...#...#..###"),
+        ('J', "..##...#...#...##..#.##."),
+        ('K', "#..##.#.##..#.#.#.#.#..#"),
+        ('L', "#...#...#...#...#...####"),
+        ('O', ".##.#..##..##..##..#.##."),
+        ('P', "###.#..##..####.#...#..."),
+        ('R', "###.#..##..####.#.#.#..#"),
+        ('S', ".####...#....##....####."),
+        ('U', "#..##..##..##..##..#.##."),
+        ('Y', "#...#....#.#..#...#...#."),
+        ('Z', "####...#..#..#..#...####"),
+    ],
+);
+
+/// The 6x10 block letters, with two blank columns between letters.
+const LARGE: (usize, usize, &[(char, &str)]) = (
+    6,
+    8,
+    &[
+        (
+            'A',
+            "..##...#..#.#....##....##....########....##....##....##....#",
+        ),
+        (
+            'B',
+            "#####.#....##....##....######.#....##....##....##....######.",
+        ),
+        (
+            'C',
+            ".####.#....##.....#.....#.....#.....#.....#.....#....#.####.",
+        ),
+        (
+            'E',
+            "#######.....#.....#.....#####.#.....#.....#.....#.....######",
+        ),
+        (
+            'F',
+            "#######.....#.....#.....#####.#.....#.....#.....#.....#.....",
+        ),
+        (
+            'G',
+            ".####.#....##.....#.....#.....#..####....##....##...##.###.#",
+        ),
+        (
+            'H',
+            "#....##....##....##....########....##....##....##....##....#",
+        ),
+        (
+            'J',
+            "...###....#.....#.....#.....#.....#.....#.#...#.#...#..###..",
+        ),
+        (
+            'K',
+            "#....##...#.#..#..#.#...##....##....#.#...#..#..#...#.#....#",
+        ),
+        (
+            'L',
+            "#.....#.....#.....#.....#.....#.....#.....#.....#.....######",
+        ),
+        (
+            'N',
+            "#....###...###...##.#..##.#..##..#.##..#.##...###...###....#",
+        ),
+        (
+            'P',
+            "#####.#....##....##....######.#.....#.....#.....#.....#.....",
+        ),
+        (
+            'R',
+            "#####.#....##....##....######.#..#..#...#.#...#.#....##....#",
+        ),
+        (
+            'X',
+            "#....##....#.#..#..#..#...##....##...#..#..#..#.#....##....#",
+        ),
+        (
+            'Z',
+            "######.....#.....#....#....#....#....#....#.....#.....######",
+        ),
+    ],
+);
+
+/// Reads the capital letters drawn in `render` with `#` for lit pixels, in
+/// either the 4x6 or the 6x10 font. `None` if the render is not made of
+/// letters in one of the fonts.
+pub fn recognize(render: &str) -> Option<String> {
+    let rows: Vec<&[u8]> = render
+        .lines()
+        .map(str::as_bytes)
+        .filter(|row| !row.is_empty())
+        .collect();
+    let (width, stride, glyphs) = match rows.len() {
+        6 => SMALL,
+        10 => LARGE,
+        _ => return None,
+    };
+
+    let columns = rows.iter().map(|row| row.len()).max()?;
+    let mut letters = String::new();
+    for start in (0..columns).step_by(stride) {
+        let pixels: String = rows
+            .iter()
+            .flat_map(|row| (start..start + width).map(|x| row.get(x) == Some(&b'#')))
+            .map(|lit| if lit { '#' } else { '.' })
+            .collect();
+        if !pixels.contains('#') {
+            continue;
+        }
+        let (letter, _) = glyphs.iter().find(|(_, glyph)| *glyph == pixels)?;
+        letters.push(*letter);
+    }
+    (!letters.is_empty()).then_some(letters)
+}
+
+/// A rendered screen, displayed as the letters it shows if they can be
+/// read and as the render itself otherwise. The alternate form (`{:#}`)
+/// always includes the render.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screen {
+    pub render: String,
+}
+
+impl Display for Screen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match recognize(&self.render) {
+            Some(letters) if f.alternate() => {
+                write!(f, "{}\n{}", letters, self.render.trim_start())
+            }
+            Some(letters) => write!(f, "{}", letters),
+            None => write!(f, "{}", self.render),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_letters() {
+        let render = "
+###..#....###...##..####.###...##..#....
+#..#.#....#..#.#..#.#....#..#.#..#.#....
+#..#.#....#..#.#..#.###..###..#....#....
+###..#....###..####.#....#..#.#....#....
+#....#....#....#..#.#....#..#.#..#.#....
+#....####.#....#..#.#....###...##..####.";
+        assert_eq!(recognize(render).as_deref(), Some("PLPAFBCL"));
+
+        let screen = Screen {
+            render: String::from(render),
+        };
+        assert_eq!(screen.to_string(), "PLPAFBCL");
+        assert_eq!(format!("{:#}", screen), format!("PLPAFBCL{}", render));
+    }
+
+    #[test]
+    fn large_letters() {
+        let render = "
+#....#..######
+#....#.......#
+#....#.......#
+#....#......#.
+######.....#..
+#....#....#...
+#....#...#....
+#....#..#.....
+#....#..#.....
+#....#..######";
+        assert_eq!(recognize(render).as_deref(), Some("HZ"));
+    }
+
+    #[test]
+    fn unknown_shapes() {
+        let render = "
+##..##..
+###...##
+####....
+#####...
+######..
+#######.";
+        assert_eq!(recognize(render), None);
+        let screen = Screen {
+            render: String::from(render),
+        };
+        assert_eq!(screen.to_string(), render);
+    }
+}
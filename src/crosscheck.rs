@@ -0,0 +1,159 @@
+use crate::report::DayReport;
+use crate::timing::format_duration;
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+/// How the implementations of one day fared on the same input.
+pub struct CrossCheck {
+    pub year: u32,
+    pub day: u32,
+    /// Parse plus solve time of each implementation that answered every
+    /// part, fastest first.
+    pub timings: Vec<(&'static str, Duration)>,
+    pub disagreements: Vec<Disagreement>,
+}
+
+/// A part the implementations gave different answers for.
+pub struct Disagreement {
+    pub part: u32,
+    pub answers: Vec<(&'static str, String)>,
+}
+
+impl CrossCheck {
+    pub fn agrees(&self) -> bool {
+        self.disagreements.is_empty()
+    }
+}
+
+/// Compares the answers and timings of every day run by more than one
+/// implementation, if any of them got past parsing. Parts that timed out or
+/// panicked are left out.
+pub fn cross_check(reports: &[DayReport]) -> Vec<CrossCheck> {
+    let mut days: Vec<Vec<&DayReport>> = Vec::new();
+    for report in reports {
+        match days
+            .iter_mut()
+            .find(|d| (d[0].year, d[0].day) == (report.year, report.day))
+        {
+            Some(day) => day.push(report),
+            None => days.push(vec![report]),
+        }
+    }
+
+    days.into_iter()
+        .filter(|day| day.len() > 1 && day.iter().any(|r| r.result.is_ok()))
+        .map(|day| {
+            let mut timings: Vec<_> = day
+                .iter()
+                .filter(|r| {
+                    r.result
+                        .as_ref()
+                        .is_ok_and(|results| results.parts.iter().all(|p| p.answer.is_ok()))
+                })
+                .map(|r| (r.implementation, r.parse_time() + r.runtime()))
+                .collect();
+            timings.sort_by_key(|(_, time)| *time);
+
+            let mut disagreements = Vec::new();
+            for &part in &day[0].parts {
+                let answers: Vec<_> = day
+                    .iter()
+                    .filter_map(|r| {
+                        let results = r.result.as_ref().ok()?;
+                        let answer = results.parts.iter().find(|p| p.part == part)?;
+                        Some((r.implementation, answer.answer.clone().ok()?))
+                    })
+                    .collect();
+                if answers.iter().any(|(_, a)| *a != answers[0].1) {
+                    disagreements.push(Disagreement { part, answers });
+                }
+            }
+
+            CrossCheck {
+                year: day[0].year,
+                day: day[0].day,
+                timings,
+                disagreements,
+            }
+        })
+        .collect()
+}
+
+impl Display for CrossCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} day {}:", self.year, self.day)?;
+        match self.timings.split_first() {
+            Some((&(name, fastest), slower)) => {
+                write!(f, " fastest {} in {}", name, format_duration(fastest))?;
+                for (name, time) in slower {
+                    let ratio = time.as_secs_f64() / fastest.as_secs_f64().max(1e-9);
+                    write!(
+                        f,
+                        ", {} in {} ({:.1}x)",
+                        name,
+                        format_duration(*time),
+                        ratio
+                    )?;
+                }
+            }
+            None => write!(f, " no implementation answered every part")?,
+        }
+        for d in &self.disagreements {
+            write!(f, "\n  Part {} DISAGREE:", d.part)?;
+            for (name, answer) in &d.answers {
+                write!(f, "\n    {}: {}", name, answer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::harness::DEFAULT_IMPLEMENTATION;
+    use crate::report::fixture::report;
+    use crate::report::{DayError, PartFailure};
+
+    #[test]
+    fn fastest_and_disagreements() {
+        let reports = [
+            report(
+                5,
+                DEFAULT_IMPLEMENTATION,
+                vec![Ok("CMZ"), Ok("MCD")],
+                10,
+                10,
+            ),
+            report(6, DEFAULT_IMPLEMENTATION, vec![Ok("7"), Ok("19")], 30, 30),
+            report(6, "bitmask", vec![Ok("7"), Ok("19")], 10, 10),
+            report(6, "broken", vec![Ok("8"), Err(PartFailure::Panic)], 1, 1),
+            DayReport {
+                result: Err(DayError::MissingInput),
+                ..report(7, DEFAULT_IMPLEMENTATION, vec![Ok(""), Ok("")], 0, 0)
+            },
+            DayReport {
+                result: Err(DayError::MissingInput),
+                ..report(7, "other", vec![Ok(""), Ok("")], 0, 0)
+            },
+        ];
+        let checks = cross_check(&reports);
+        assert_eq!(checks.len(), 1);
+
+        let check = &checks[0];
+        assert_eq!(check.day, 6);
+        assert!(!check.agrees());
+        assert_eq!(
+            check.timings,
+            [
+                ("bitmask", Duration::from_micros(30)),
+                (DEFAULT_IMPLEMENTATION, Duration::from_micros(90)),
+            ]
+        );
+        assert_eq!(
+            check.to_string(),
+            "2022 day 6: fastest bitmask in 30 µs, default in 90 µs (3.0x)\n  \
+             Part 1 DISAGREE:\n    default: 7\n    bitmask: 7\n    broken: 8"
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use crate::registry;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// A random input for `year` and `day` from the day's generator, growing
+/// with `size`. The same seed gives the same input.
+pub fn generate(year: u32, day: u32, size: usize, seed: u64) -> Result<String, String> {
+    let solution = registry::all()
+        .find(|s| (s.year(), s.day()) == (year, day))
+        .ok_or_else(|| format!("No solution for {} day {}", year, day))?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    solution
+        .generate(&mut rng, size)
+        .ok_or_else(|| format!("No input generator for {} day {}", year, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::Params;
+
+    #[test]
+    fn generated_inputs_solve_alike() {
+        let days: Vec<_> = registry::all().map(|s| (s.year(), s.day())).collect();
+        let mut generated = 0;
+        for (year, day) in days {
+            for seed in 0..3 {
+                let Ok(input) = generate(year, day, 40, seed) else {
+                    continue;
+                };
+                assert_eq!(generate(year, day, 40, seed).as_ref(), Ok(&input));
+                generated += 1;
+
+                let answers: Vec<_> = registry::implementations()
+                    .filter(|s| (s.year(), s.day()) == (year, day))
+                    .map(|s| {
+                        let parsed = s.parse(input.clone()).unwrap_or_else(|e| {
+                            panic!("{} day {} seed {}: {}\n{}", year, day, seed, e, input)
+                        });
+                        let params = Params::default();
+                        let part1 = s.part1(parsed.as_ref(), &params).to_string();
+                        (part1, s.part2(parsed.as_ref(), &params).to_string())
+                    })
+                    .collect();
+                assert!(
+                    answers.windows(2).all(|w| w[0] == w[1]),
+                    "{} day {} seed {}: {:?}",
+                    year,
+                    day,
+                    seed,
+                    answers
+                );
+            }
+        }
+        let with_generator = registry::all()
+            .filter(|s| s.generate(&mut StdRng::seed_from_u64(0), 1).is_some())
+            .count();
+        assert!(with_generator > 0);
+        assert_eq!(generated, with_generator * 3);
+        assert!(generate(2015, 1, 40, 0)
+            .unwrap_err()
+            .contains("No input generator"));
+    }
+}
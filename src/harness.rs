@@ -1,18 +1,221 @@
-use std::time::{Duration, Instant};
+use crate::alloc::{self, AllocStats};
+use crate::answers::Answers;
+use crate::params::{Param, Params};
+use crate::parse::ParseError;
+use crate::report::{DayError, DayReport, DayResults, PartFailure, PartReport};
+use crate::timing::{measure, Stats};
+use rand::rngs::StdRng;
+use std::any::Any;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
-pub fn run(year: u32, day: u32, solution: impl Harness) -> Duration {
-    let path = format!("input/{}/day{:02}", year, day);
-    let Ok(raw) = std::fs::read_to_string(path) else {
-        println!("Skipping {} day {}, input file missing.", year, day);
-        return Duration::default();
+/// Settings shared by every day of a run.
+#[derive(Default)]
+pub struct Config {
+    /// `None` runs both parts.
+    pub part: Option<u32>,
+    pub input: InputSource,
+    /// Compare answers against `answers/{year}.toml`.
+    pub check: bool,
+    /// Number of timed runs of parse and each part, reported as statistics.
+    pub bench: Option<usize>,
+    pub timeouts: Timeouts,
+    /// Report answers in their alternate, more detailed form (`{:#}`).
+    pub verbose: bool,
+    /// Overrides of the solutions' puzzle constants.
+    pub params: Params,
+}
+
+impl Config {
+    fn includes_part(&self, part: u32) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+}
+
+/// Time budget for each run of a part, set globally or for specific days.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Timeouts {
+    pub global: Option<Duration>,
+    /// Overrides of the global budget, keyed by year and day.
+    pub days: Vec<((u32, u32), Duration)>,
+}
+
+impl Timeouts {
+    pub fn for_day(&self, year: u32, day: u32) -> Option<Duration> {
+        self.days
+            .iter()
+            .rev()
+            .find(|(d, _)| *d == (year, day))
+            .map(|(_, budget)| *budget)
+            .or(self.global)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// `input/{year}/dayNN` relative to the working directory.
+    #[default]
+    Default,
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    pub fn default_path(year: u32, day: u32) -> PathBuf {
+        PathBuf::from(format!("input/{}/day{:02}", year, day))
+    }
+
+    fn read(&self, year: u32, day: u32) -> std::io::Result<String> {
+        match self {
+            Self::Default => std::fs::read_to_string(Self::default_path(year, day)),
+            Self::File(path) => std::fs::read_to_string(path),
+            Self::Stdin => std::io::read_to_string(std::io::stdin()),
+        }
+    }
+}
+
+pub fn run(solution: &'static dyn DynHarness, config: &Config) -> DayReport {
+    let (year, day) = (solution.year(), solution.day());
+    DayReport {
+        year,
+        day,
+        implementation: solution.name(),
+        parts: [1, 2]
+            .into_iter()
+            .filter(|p| config.includes_part(*p))
+            .collect(),
+        checked: config.check,
+        result: run_parts(solution, config),
+    }
+}
+
+fn run_parts(solution: &'static dyn DynHarness, config: &Config) -> Result<DayResults, DayError> {
+    let (year, day) = (solution.year(), solution.day());
+    let raw = match config.input.read(year, day) {
+        Ok(raw) => raw,
+        Err(_) if config.input == InputSource::Default => return Err(DayError::MissingInput),
+        Err(e) => return Err(DayError::UnreadableInput(e.to_string())),
     };
-    let mut runtime = Duration::default();
+    let answers = config
+        .check
+        .then(|| Answers::load(year))
+        .transpose()
+        .map_err(DayError::Answers)?;
+    let runs = config.bench.unwrap_or(1);
+
+    let mut parse_times = Vec::with_capacity(runs);
+    let mut parse_alloc = None;
+    let mut input = None;
+    for _ in 0..runs {
+        let raw = raw.clone();
+        let ((parsed, alloc), time) = measure(|| alloc::track(|| solution.parse(raw)));
+        parse_times.push(time);
+        parse_alloc = parse_alloc.or(alloc);
+        input = Some(parsed.map_err(DayError::Parse)?);
+    }
+    let input: Input = Arc::from(input.expect("at least one run"));
+    let budget = config.timeouts.for_day(year, day);
+
+    let mut parts = Vec::new();
+    for part in [1, 2].into_iter().filter(|p| config.includes_part(*p)) {
+        let mut times = Vec::with_capacity(runs);
+        let mut result = None;
+        let mut alloc = None;
+        for _ in 0..runs {
+            let (r, time) = run_part(solution, &input, part, &config.params, budget);
+            times.push(time);
+            match r {
+                Ok((r, a)) => {
+                    alloc = alloc.or(a);
+                    result.get_or_insert(Ok(r));
+                }
+                Err(failure) => {
+                    result = Some(Err(failure));
+                    break;
+                }
+            }
+        }
 
-    println!("Day {}", day);
-    let input = solution.parse(raw);
-    runtime += measure(|| solution.part1(&input), 1);
-    runtime += measure(|| solution.part2(&input), 2);
-    runtime
+        let result = result.expect("at least one run");
+        // Verdicts are on the plain answer, whatever form is reported, and
+        // only for the puzzle as given.
+        let verdict = match &result {
+            Ok(_) if config.params.overrides(solution.params()) => None,
+            Ok(r) => answers
+                .as_ref()
+                .map(|a| a.verdict(day, part, &r.to_string())),
+            Err(_) => None,
+        };
+        let answer = result.map(|r| {
+            if config.verbose {
+                format!("{:#}", r)
+            } else {
+                r.to_string()
+            }
+        });
+        parts.push(PartReport {
+            part,
+            verdict,
+            answer,
+            time: Stats::from_samples(&times),
+            alloc,
+        });
+    }
+
+    Ok(DayResults {
+        parse_time: Stats::from_samples(&parse_times),
+        parse_alloc,
+        parts,
+    })
+}
+
+type Input = Arc<dyn Any + Send + Sync>;
+type Answer = Box<dyn Display + Send>;
+
+/// Runs one part, on a watchdog thread when it has a time budget. A part
+/// that overruns cannot be stopped, so its thread is left running detached.
+fn run_part(
+    solution: &'static dyn DynHarness,
+    input: &Input,
+    part: u32,
+    params: &Params,
+    budget: Option<Duration>,
+) -> (Result<(Answer, Option<AllocStats>), PartFailure>, Duration) {
+    let params = params.clone();
+    let solve = move |input: &Input| {
+        measure(|| {
+            alloc::track(|| match part {
+                1 => solution.part1(input.as_ref(), &params),
+                _ => solution.part2(input.as_ref(), &params),
+            })
+        })
+    };
+
+    let Some(budget) = budget else {
+        let (answer, time) = solve(input);
+        return (Ok(answer), time);
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let input = Arc::clone(input);
+    let name = format!("{} day {} part {}", solution.year(), solution.day(), part);
+    let spawned = thread::Builder::new().name(name).spawn(move || {
+        let _ = tx.send(solve(&input));
+    });
+    if spawned.is_err() {
+        return (Err(PartFailure::Panic), Duration::ZERO);
+    }
+
+    let (received, waited) = measure(|| rx.recv_timeout(budget));
+    match received {
+        Ok((answer, time)) => (Ok(answer), time),
+        Err(RecvTimeoutError::Timeout) => (Err(PartFailure::Timeout(budget)), waited),
+        Err(RecvTimeoutError::Disconnected) => (Err(PartFailure::Panic), waited),
+    }
 }
 
 pub trait Harness {
@@ -20,29 +223,248 @@ pub trait Harness {
     type Part1Output: std::fmt::Display;
     type Part2Output: std::fmt::Display;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed;
+    /// Puzzle constants that `--param` can override. Solutions declaring
+    /// any implement `part1_with` and `part2_with`, and have `part1` and
+    /// `part2` call them with the defaults.
+    const PARAMS: &'static [Param] = &[];
+
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError>;
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output;
     fn part2(&self, input: &Self::Parsed) -> Self::Part2Output;
+
+    fn part1_with(&self, input: &Self::Parsed, _params: &Params) -> Self::Part1Output {
+        self.part1(input)
+    }
+
+    fn part2_with(&self, input: &Self::Parsed, _params: &Params) -> Self::Part2Output {
+        self.part2(input)
+    }
+
+    /// A random, valid input that grows with `size`, for stress tests and
+    /// benchmarks. `None` if the day has no generator.
+    fn generate(&self, _rng: &mut StdRng, _size: usize) -> Option<String> {
+        None
+    }
+}
+
+/// Object safe view of a [`Harness`] registered for a specific year and day.
+///
+/// The parsed input is passed around as `dyn Any` and must have come from
+/// the same solution's `parse`.
+pub trait DynHarness: Sync {
+    fn year(&self) -> u32;
+    fn day(&self) -> u32;
+    /// Tells implementations of the same day apart; the day's `Solution` is
+    /// [`DEFAULT_IMPLEMENTATION`].
+    fn name(&self) -> &'static str;
+    fn params(&self) -> &'static [Param];
+    fn parse(&self, raw_input: String) -> Result<Box<dyn Any + Send + Sync>, ParseError>;
+    fn part1(&self, input: &dyn Any, params: &Params) -> Box<dyn Display + Send>;
+    fn part2(&self, input: &dyn Any, params: &Params) -> Box<dyn Display + Send>;
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String>;
+}
+
+pub const DEFAULT_IMPLEMENTATION: &str = "default";
+
+pub struct Registered<H> {
+    year: u32,
+    day: u32,
+    name: &'static str,
+    solution: H,
 }
 
-fn measure<F, O>(f: F, part: u32) -> Duration
+impl<H> Registered<H> {
+    pub const fn new(year: u32, day: u32, solution: H) -> Self {
+        Self::named(year, day, DEFAULT_IMPLEMENTATION, solution)
+    }
+
+    /// Registers an alternative implementation of a day.
+    pub const fn named(year: u32, day: u32, name: &'static str, solution: H) -> Self {
+        Self {
+            year,
+            day,
+            name,
+            solution,
+        }
+    }
+
+    fn downcast<'a>(&self, input: &'a dyn Any) -> &'a H::Parsed
+    where
+        H: Harness,
+        H::Parsed: 'static,
+    {
+        input
+            .downcast_ref()
+            .expect("parsed input belongs to a different solution")
+    }
+}
+
+impl<H> DynHarness for Registered<H>
 where
-    F: FnOnce() -> O,
-    O: std::fmt::Display,
+    H: Harness + Sync,
+    H::Parsed: Send + Sync + 'static,
+    H::Part1Output: Send + 'static,
+    H::Part2Output: Send + 'static,
 {
-    let start = Instant::now();
-    let result = f();
-    let end = Instant::now();
-    println!("  Part {}: {}", part, result);
+    fn year(&self) -> u32 {
+        self.year
+    }
+
+    fn day(&self) -> u32 {
+        self.day
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn parse(&self, raw_input: String) -> Result<Box<dyn Any + Send + Sync>, ParseError> {
+        let parsed = self.solution.parse(raw_input)?;
+        Ok(Box::new(parsed))
+    }
+
+    fn params(&self) -> &'static [Param] {
+        H::PARAMS
+    }
+
+    fn part1(&self, input: &dyn Any, params: &Params) -> Box<dyn Display + Send> {
+        Box::new(self.solution.part1_with(self.downcast(input), params))
+    }
+
+    fn part2(&self, input: &dyn Any, params: &Params) -> Box<dyn Display + Send> {
+        Box::new(self.solution.part2_with(self.downcast(input), params))
+    }
+
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        self.solution.generate(rng, size)
+    }
+}
 
-    let runtime = end - start;
-    if runtime.as_millis() > 1 {
-        println!("    Runtime: {} ms", runtime.as_millis());
-    } else if runtime.as_micros() > 1 {
-        println!("    Runtime: {} \u{00b5}s", runtime.as_micros());
-    } else {
-        println!("    Runtime: {} ns", runtime.as_nanos());
+/// Declares the day modules of a year and registers their `Solution`s in
+/// a `SOLUTIONS` slice, so each new day is a single line. Alternative
+/// implementations follow their day's module as `{ name: Type }`:
+///
+/// ```ignore
+/// register_days! {
+///     2022;
+///     5 => day05,
+///     6 => day06 { bitmask: Bitmask },
+/// }
+/// ```
+#[macro_export]
+macro_rules! register_days {
+    ($year:literal; $($day:literal => $module:ident $({ $($name:ident: $implementation:ident),* $(,)? })?),* $(,)?) => {
+        $(mod $module;)*
+
+        pub static SOLUTIONS: &[&dyn $crate::harness::DynHarness] = &[
+            $(
+                &$crate::harness::Registered::new($year, $day, $module::Solution),
+                $($(&$crate::harness::Registered::named(
+                    $year,
+                    $day,
+                    stringify!($name),
+                    $module::$implementation,
+                ),)*)?
+            )*
+        ];
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Part 1 answers at once, part 2 runs far past any budget.
+    struct Slow;
+
+    /// Part 1 answers at once, part 2 panics.
+    struct Panicking;
+
+    impl Harness for Slow {
+        type Parsed = ();
+        type Part1Output = u32;
+        type Part2Output = u32;
+
+        fn parse(&self, _raw_input: String) -> Result<Self::Parsed, ParseError> {
+            Ok(())
+        }
+
+        fn part1(&self, _input: &Self::Parsed) -> Self::Part1Output {
+            1
+        }
+
+        fn part2(&self, _input: &Self::Parsed) -> Self::Part2Output {
+            thread::sleep(Duration::from_secs(10));
+            2
+        }
     }
 
-    runtime
+    impl Harness for Panicking {
+        type Parsed = ();
+        type Part1Output = u32;
+        type Part2Output = u32;
+
+        fn parse(&self, _raw_input: String) -> Result<Self::Parsed, ParseError> {
+            Ok(())
+        }
+
+        fn part1(&self, _input: &Self::Parsed) -> Self::Part1Output {
+            1
+        }
+
+        fn part2(&self, _input: &Self::Parsed) -> Self::Part2Output {
+            panic!("no answer")
+        }
+    }
+
+    static SLOW: Registered<Slow> = Registered::new(2022, 98, Slow);
+    static PANICKING: Registered<Panicking> = Registered::new(2022, 99, Panicking);
+
+    fn run_with_budget(solution: &'static dyn DynHarness, budget: Duration) -> DayReport {
+        let path = std::env::temp_dir().join(format!(
+            "aoc-watchdog-{}-{}",
+            std::process::id(),
+            solution.day()
+        ));
+        std::fs::write(&path, "").unwrap();
+        let config = Config {
+            input: InputSource::File(path.clone()),
+            timeouts: Timeouts {
+                global: Some(budget),
+                days: Vec::new(),
+            },
+            ..Config::default()
+        };
+        let report = run(solution, &config);
+        std::fs::remove_file(&path).unwrap();
+        report
+    }
+
+    fn answers(report: &DayReport) -> Vec<Result<String, PartFailure>> {
+        let Ok(results) = &report.result else {
+            panic!("day did not run");
+        };
+        results.parts.iter().map(|p| p.answer.clone()).collect()
+    }
+
+    #[test]
+    fn watchdog_reports_timeout() {
+        let budget = Duration::from_millis(50);
+        let report = run_with_budget(&SLOW, budget);
+        assert_eq!(
+            answers(&report),
+            [Ok(String::from("1")), Err(PartFailure::Timeout(budget))]
+        );
+        assert!(report.failed());
+    }
+
+    #[test]
+    fn watchdog_reports_panic() {
+        let report = run_with_budget(&PANICKING, Duration::from_secs(10));
+        assert_eq!(
+            answers(&report),
+            [Ok(String::from("1")), Err(PartFailure::Panic)]
+        );
+        assert!(report.failed());
+    }
 }
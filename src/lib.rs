@@ -0,0 +1,68 @@
+//! Advent of Code solutions behind a common [`Harness`], the algorithms
+//! they share in [`common`], and the tooling the runner binary is built on.
+
+pub mod alloc;
+pub mod answers;
+pub mod baseline;
+pub mod client;
+pub mod common;
+pub mod crosscheck;
+pub mod generate;
+pub mod harness;
+pub mod params;
+pub mod parse;
+pub mod registry;
+pub mod report;
+#[cfg(test)]
+mod samples;
+pub mod scaffold;
+pub mod submit;
+pub mod timing;
+mod year2015;
+mod year2022;
+
+pub use harness::Harness;
+
+use params::Params;
+use parse::ParseError;
+use std::fmt::{self, Display};
+
+// Lets the allocation tests count; the binary installs its own.
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: alloc::Counting = alloc::Counting;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// No solution is registered for the year and day.
+    UnknownDay {
+        year: u32,
+        day: u32,
+    },
+    Parse(ParseError),
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownDay { year, day } => write!(f, "no solution for {} day {}", year, day),
+            Self::Parse(e) => write!(f, "parse error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Solves both parts of `year` and `day` for `input`.
+pub fn solve(year: u32, day: u32, input: &str) -> Result<(String, String), SolveError> {
+    let solution = registry::all()
+        .find(|s| (s.year(), s.day()) == (year, day))
+        .ok_or(SolveError::UnknownDay { year, day })?;
+    let parsed = solution
+        .parse(input.to_string())
+        .map_err(SolveError::Parse)?;
+    let params = Params::default();
+    let part1 = solution.part1(parsed.as_ref(), &params).to_string();
+    let part2 = solution.part2(parsed.as_ref(), &params).to_string();
+    Ok((part1, part2))
+}
@@ -1,19 +1,301 @@
-mod common;
-mod harness;
-mod year2015;
-mod year2022;
+mod cli;
+
+use advent_of_code_2022::baseline::Baseline;
+use advent_of_code_2022::client::{self, Client, ClientConfig, Fetched};
+use advent_of_code_2022::harness::{self, DynHarness, InputSource, DEFAULT_IMPLEMENTATION};
+use advent_of_code_2022::report::{self, DayReport, Format, TextPrinter};
+use advent_of_code_2022::submit::{self, Outcome, SubmissionLog};
+use advent_of_code_2022::timing::format_duration;
+use advent_of_code_2022::{alloc, crosscheck, generate, registry, scaffold};
+use cli::{Command, Implementation, Options};
+use rayon::prelude::*;
+use std::path::Path;
+use std::time::Instant;
+
+#[global_allocator]
+static ALLOCATOR: alloc::Counting = alloc::Counting;
 
 fn main() {
-    let mode = std::env::args()
-        .nth(1)
-        .unwrap_or_else(|| String::from("2022"));
-    match mode.as_str() {
-        "2015" => year2015::run_all(),
-        "2022" => year2022::run_all(),
-        "all" => {
-            year2015::run_all();
-            year2022::run_all();
-        }
-        _ => eprintln!("Invalid arg"),
+    let options = match cli::parse_command(std::env::args().skip(1)) {
+        Ok(Command::Run(options)) => options,
+        Ok(Command::New { year, day }) => {
+            let touched =
+                scaffold::new_day(Path::new("."), year, day).unwrap_or_else(|e| exit_with(&e));
+            for path in touched {
+                println!("{}", path.display());
+            }
+            return;
+        }
+        Ok(Command::Fetch(options)) => {
+            let selected: Vec<_> = registry::all()
+                .filter(|s| options.includes_year(s.year()) && options.includes_day(s.day()))
+                .collect();
+            let failed = fetch_inputs(&selected, true);
+            std::process::exit(i32::from(failed));
+        }
+        Ok(Command::Submit {
+            year,
+            day,
+            part,
+            answer,
+        }) => {
+            let answer = answer.unwrap_or_else(|| solve(year, day, part));
+            let config = ClientConfig::load().unwrap_or_else(|e| exit_with(&e));
+            let mut log = SubmissionLog::load(Path::new(SubmissionLog::PATH))
+                .unwrap_or_else(|e| exit_with(&e));
+            println!("Submitting {} day {} part {}: {}", year, day, part, answer);
+            let outcome = submit::submit(
+                &mut Client::new(config),
+                &mut log,
+                (year, day, part),
+                &answer,
+            )
+            .unwrap_or_else(|e| exit_with(&e));
+            println!("{}", outcome);
+            std::process::exit(i32::from(outcome != Outcome::Correct));
+        }
+        Ok(Command::Generate {
+            year,
+            day,
+            size,
+            seed,
+            output,
+        }) => {
+            let input = generate::generate(year, day, size, seed).unwrap_or_else(|e| exit_with(&e));
+            match output {
+                Some(path) => std::fs::write(&path, input)
+                    .unwrap_or_else(|e| exit_with(&format!("{}: {}", path.display(), e))),
+                None => print!("{}", input),
+            }
+            return;
+        }
+        Err(e) => exit_with(&e),
+    };
+
+    let selected = select(&options);
+    let mut days: Vec<_> = selected.iter().map(|s| (s.year(), s.day())).collect();
+    days.dedup();
+    if options.config.input != InputSource::Default && days.len() != 1 {
+        eprintln!("--input requires selecting exactly one year and day");
+        std::process::exit(1);
     }
+    for name in options.config.params.names() {
+        if !selected
+            .iter()
+            .any(|s| s.params().iter().any(|p| p.name == name))
+        {
+            exit_with(&unknown_param(name, &selected));
+        }
+    }
+    for s in &selected {
+        options
+            .config
+            .params
+            .check(s.params())
+            .unwrap_or_else(|e| exit_with(&e));
+    }
+
+    if options.alloc {
+        if options.parallel {
+            exit_with("--alloc cannot be combined with --parallel");
+        }
+        alloc::enable();
+    }
+
+    // Loaded up front so that a missing baseline fails before any day runs.
+    let compare = options
+        .compare
+        .as_ref()
+        .map(|path| Baseline::load(path).unwrap_or_else(|e| exit_with(&e)));
+
+    if options.fetch && options.config.input == InputSource::Default {
+        // Days whose input could not be fetched are skipped as usual.
+        fetch_inputs(&selected, false);
+    }
+
+    let text = options.format == Format::Text;
+    let bench = options.config.bench.is_some();
+    let mut printer = text.then(|| TextPrinter::new(bench, options.parallel));
+    let start = Instant::now();
+
+    let reports: Vec<DayReport> = if options.parallel {
+        // Days run concurrently but are printed in order once all are done.
+        let reports: Vec<_> = selected
+            .par_iter()
+            .map(|s| harness::run(*s, &options.config))
+            .collect();
+        if let Some(printer) = printer.as_mut() {
+            reports.iter().for_each(|r| printer.print(r));
+        }
+        reports
+    } else {
+        selected
+            .iter()
+            .map(|s| {
+                let report = harness::run(*s, &options.config);
+                if let Some(printer) = printer.as_mut() {
+                    printer.print(&report);
+                }
+                report
+            })
+            .collect()
+    };
+
+    let wall_clock = start.elapsed();
+    if let Some(printer) = printer.as_mut() {
+        printer.finish_year();
+        if options.parallel {
+            println!("Wall-clock time: {}", format_duration(wall_clock));
+        }
+    }
+
+    let records: Vec<_> = reports.iter().flat_map(|r| r.records()).collect();
+    match options.format {
+        Format::Text => {}
+        Format::Json => println!("{}", report::to_json(&records)),
+        Format::Csv => print!("{}", report::to_csv(&records)),
+    }
+
+    let mut regressed = false;
+    if let Some(baseline) = &compare {
+        let comparisons = baseline.compare(&reports, options.threshold);
+        let mut lines = vec![format!(
+            "==== Baseline comparison (threshold {}%) ====",
+            options.threshold
+        )];
+        lines.extend(comparisons.iter().map(|c| c.to_string()));
+        print_summary(&lines, text);
+        regressed = comparisons.iter().any(|c| c.regressed);
+    }
+
+    let mut disagreed = false;
+    if options.implementation == Implementation::All {
+        let checks = crosscheck::cross_check(&reports);
+        let mut lines = vec![String::from("==== Implementations ====")];
+        lines.extend(checks.iter().map(|c| c.to_string()));
+        print_summary(&lines, text);
+        disagreed = checks.iter().any(|c| !c.agrees());
+    }
+
+    if let Some(path) = &options.save_baseline {
+        let mut baseline = Baseline::load_or_default(path).unwrap_or_else(|e| exit_with(&e));
+        baseline.update(&reports);
+        baseline.save(path).unwrap_or_else(|e| exit_with(&e));
+    }
+
+    if regressed || disagreed || reports.iter().any(|r| r.failed()) {
+        std::process::exit(1);
+    }
+}
+
+/// The solutions to run: the selected days' default implementations, or the
+/// ones asked for with `--impl`.
+fn select(options: &Options) -> Vec<&'static dyn DynHarness> {
+    let days = || {
+        registry::implementations()
+            .filter(|s| options.includes_year(s.year()) && options.includes_day(s.day()))
+    };
+    let is_default = |s: &&dyn DynHarness| s.name() == DEFAULT_IMPLEMENTATION;
+    match &options.implementation {
+        Implementation::Default => days().filter(is_default).collect(),
+        Implementation::All => days().collect(),
+        Implementation::Named(name) => {
+            let named: Vec<_> = days().filter(|s| s.name() == name).collect();
+            if named.is_empty() {
+                exit_with(&format!(
+                    "No selected day has an implementation named {}",
+                    name
+                ));
+            }
+            let day = |s: &&dyn DynHarness| (s.year(), s.day());
+            days()
+                .filter(|s| {
+                    if is_default(s) {
+                        !named.iter().any(|n| day(n) == day(s))
+                    } else {
+                        s.name() == name
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+/// Prints a summary after the reports, on stderr unless the output is text
+/// to keep machine-readable output on stdout parseable.
+fn print_summary(lines: &[String], text: bool) {
+    for line in lines {
+        if text {
+            println!("{}", line);
+        } else {
+            eprintln!("{}", line);
+        }
+    }
+}
+
+/// Downloads the missing inputs of `solutions`, reporting progress on stderr.
+/// Returns whether any download failed.
+fn fetch_inputs(solutions: &[&dyn DynHarness], verbose: bool) -> bool {
+    let config = ClientConfig::load().unwrap_or_else(|e| exit_with(&e));
+    let mut client = Client::new(config);
+    let mut failed = false;
+    for s in solutions {
+        let (year, day) = (s.year(), s.day());
+        match client::fetch_input(
+            &mut client,
+            year,
+            day,
+            &InputSource::default_path(year, day),
+        ) {
+            Ok(Fetched::Cached) if verbose => eprintln!("{} day {}: cached", year, day),
+            Ok(Fetched::Cached) => {}
+            Ok(Fetched::Downloaded) => eprintln!("{} day {}: downloaded", year, day),
+            Err(e) => {
+                eprintln!("{} day {}: {}", year, day, e);
+                failed = true;
+            }
+        }
+    }
+    failed
+}
+
+/// Runs one part on its default input, exiting if it has no single-line
+/// answer.
+fn solve(year: u32, day: u32, part: u32) -> String {
+    let solution = registry::all()
+        .find(|s| (s.year(), s.day()) == (year, day))
+        .unwrap_or_else(|| exit_with(&format!("No solution for {} day {}", year, day)));
+    let config = harness::Config {
+        part: Some(part),
+        ..Default::default()
+    };
+    let report = harness::run(solution, &config);
+    let answer = match &report.result {
+        Ok(results) => results.parts.first().and_then(|p| p.answer.as_ref().ok()),
+        Err(_) => None,
+    };
+    match answer {
+        Some(answer) if !answer.contains('\n') => answer.clone(),
+        Some(_) => exit_with("The answer spans several lines; pass it with --answer"),
+        None => exit_with(report.text(false).trim_end()),
+    }
+}
+
+fn unknown_param(name: &str, solutions: &[&dyn DynHarness]) -> String {
+    let mut message = format!("No selected day takes the parameter {}", name);
+    for s in solutions.iter().filter(|s| !s.params().is_empty()) {
+        message += &format!("\n{} day {}:", s.year(), s.day());
+        for p in s.params() {
+            message += &format!(
+                "\n  {} (default {}, {}..={}): {}",
+                p.name, p.default, p.min, p.max, p.help
+            );
+        }
+    }
+    message
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
 }
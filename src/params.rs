@@ -0,0 +1,107 @@
+/// A puzzle constant that a solution lets the command line override with
+/// `--param name=value`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: i64,
+    /// Smallest value the solution can take.
+    pub min: i64,
+    /// Largest value the solution can take.
+    pub max: i64,
+    pub help: &'static str,
+}
+
+/// Parameter values overriding the declared defaults.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Params {
+    values: Vec<(String, i64)>,
+}
+
+impl Params {
+    /// Sets `name`, replacing any earlier value.
+    pub fn set(&mut self, name: &str, value: i64) {
+        self.values.retain(|(n, _)| n != name);
+        self.values.push((name.to_string(), value));
+    }
+
+    /// Builder form of [`Params::set`].
+    pub fn with(mut self, name: &str, value: i64) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// The value of `param`: its override if there is one, else its default.
+    pub fn get(&self, param: &Param) -> i64 {
+        self.values
+            .iter()
+            .find(|(name, _)| name == param.name)
+            .map_or(param.default, |(_, value)| *value)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.values.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// An error naming the first of `declared` overridden outside its range.
+    pub fn check(&self, declared: &[Param]) -> Result<(), String> {
+        for param in declared {
+            let value = self.get(param);
+            if !(param.min..=param.max).contains(&value) {
+                return Err(format!(
+                    "Parameter {} must be in {}..={}, not {}",
+                    param.name, param.min, param.max, value
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Any of `declared` is overridden.
+    pub fn overrides(&self, declared: &[Param]) -> bool {
+        declared.iter().any(|p| self.get(p) != p.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROUNDS: Param = Param {
+        name: "rounds",
+        default: 20,
+        min: 1,
+        max: 100,
+        help: "",
+    };
+
+    #[test]
+    fn overrides_defaults() {
+        let params = Params::default();
+        assert_eq!(params.get(&ROUNDS), 20);
+        assert!(!params.overrides(&[ROUNDS]));
+
+        let params = params.with("rounds", 5).with("other", 1).with("rounds", 7);
+        assert_eq!(params.get(&ROUNDS), 7);
+        assert!(params.overrides(&[ROUNDS]));
+        assert_eq!(params.names().collect::<Vec<_>>(), ["other", "rounds"]);
+    }
+
+    #[test]
+    fn rejects_out_of_range() {
+        assert_eq!(Params::default().check(&[ROUNDS]), Ok(()));
+        let params = Params::default().with("rounds", 100);
+        assert_eq!(params.check(&[ROUNDS]), Ok(()));
+        assert_eq!(params.check(&[]), Ok(()));
+
+        for value in [0, -1, 101] {
+            let params = Params::default().with("rounds", value);
+            assert_eq!(
+                params.check(&[ROUNDS]),
+                Err(format!(
+                    "Parameter rounds must be in 1..=100, not {}",
+                    value
+                ))
+            );
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Malformed puzzle input, located by 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A single input line that knows its position, for building [`ParseError`]s.
+#[derive(Debug, Copy, Clone)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Error pointing at `token`, which should be a slice of this line.
+    pub fn error(&self, token: &str, message: impl Into<String>) -> ParseError {
+        let start = self.text.as_ptr() as usize;
+        let offset = (token.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|o| *o <= self.text.len())
+            .unwrap_or(0);
+        self.error_at(offset, message)
+    }
+
+    /// Error pointing at the 0-based byte `index` of this line.
+    pub fn error_at(&self, index: usize, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.number, index + 1, message)
+    }
+
+    /// Parses `token`, a slice of this line, reporting its position on failure.
+    pub fn parse<T: FromStr>(&self, token: &str) -> Result<T, ParseError> {
+        token
+            .parse()
+            .map_err(|_| self.error(token, format!("invalid value `{}`", token)))
+    }
+
+    /// Splits the line once on `delimiter`.
+    pub fn split_once(&self, delimiter: &str) -> Result<(&'a str, &'a str), ParseError> {
+        self.text
+            .split_once(delimiter)
+            .ok_or_else(|| self.error_at(self.text.len(), format!("expected `{}`", delimiter)))
+    }
+
+    /// Next item of `parts`, which should be split from this line.
+    pub fn expect<'b>(
+        &self,
+        parts: &mut impl Iterator<Item = &'b str>,
+        what: &str,
+    ) -> Result<&'b str, ParseError> {
+        parts
+            .next()
+            .ok_or_else(|| self.error_at(self.text.len(), format!("expected {}", what)))
+    }
+}
+
+/// Iterator over the numbered lines of an input.
+pub struct Lines<'a> {
+    inner: std::iter::Enumerate<std::str::Lines<'a>>,
+    last: usize,
+}
+
+impl<'a> Lines<'a> {
+    /// Next line, or an error if the input ended while `what` was expected.
+    pub fn expect(&mut self, what: &str) -> Result<Line<'a>, ParseError> {
+        let last = self.last;
+        self.next().ok_or_else(|| {
+            ParseError::new(
+                last + 1,
+                1,
+                format!("unexpected end of input, expected {}", what),
+            )
+        })
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, text) = self.inner.next()?;
+        self.last = i + 1;
+        Some(Line {
+            number: i + 1,
+            text,
+        })
+    }
+}
+
+pub fn lines(raw_input: &str) -> Lines<'_> {
+    Lines {
+        inner: raw_input.lines().enumerate(),
+        last: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_positions() {
+        let mut input = lines("1,2\n3,x");
+        input.next();
+        let line = input.next().unwrap();
+        let (_, b) = line.split_once(",").unwrap();
+        let err = line.parse::<u32>(b).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.to_string(), "line 2, column 3: invalid value `x`");
+
+        let err = line.split_once("-").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+
+        let err = input.expect("a third line").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+    }
+}
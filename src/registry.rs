@@ -0,0 +1,36 @@
+use crate::harness::{DynHarness, DEFAULT_IMPLEMENTATION};
+
+/// The solutions of every year, in order. `new` adds new years here.
+static YEARS: &[&[&dyn DynHarness]] = &[crate::year2015::SOLUTIONS, crate::year2022::SOLUTIONS];
+
+/// Every registered solution, ordered by year and day.
+pub fn all() -> impl Iterator<Item = &'static dyn DynHarness> {
+    implementations().filter(|s| s.name() == DEFAULT_IMPLEMENTATION)
+}
+
+/// Every implementation of every day, ordered by year and day with each
+/// day's default implementation first.
+pub fn implementations() -> impl Iterator<Item = &'static dyn DynHarness> {
+    YEARS.iter().copied().flatten().copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ordered_and_unique() {
+        let keys: Vec<_> = all().map(|s| (s.year(), s.day())).collect();
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+        assert!(keys.contains(&(2022, 14)));
+    }
+
+    #[test]
+    fn implementations_follow_their_default() {
+        let keys: Vec<_> = implementations()
+            .map(|s| (s.year(), s.day(), s.name() != DEFAULT_IMPLEMENTATION))
+            .collect();
+        assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+        assert!(keys.contains(&(2022, 6, true)));
+    }
+}
@@ -0,0 +1,400 @@
+use crate::alloc::AllocStats;
+use crate::answers::Verdict;
+use crate::harness::DEFAULT_IMPLEMENTATION;
+use crate::parse::ParseError;
+use crate::timing::{format_duration, Stats};
+use serde::Serialize;
+use std::fmt::{self, Display, Write};
+use std::time::Duration;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+/// Everything measured while running one day.
+pub struct DayReport {
+    pub year: u32,
+    pub day: u32,
+    /// Name of the implementation that ran.
+    pub implementation: &'static str,
+    /// Parts selected for the run, whether or not they got to run.
+    pub parts: Vec<u32>,
+    /// Answers were compared against the answers file.
+    pub checked: bool,
+    pub result: Result<DayResults, DayError>,
+}
+
+pub struct DayResults {
+    pub parse_time: Stats,
+    /// Only counted with `--alloc`.
+    pub parse_alloc: Option<AllocStats>,
+    pub parts: Vec<PartReport>,
+}
+
+pub struct PartReport {
+    pub part: u32,
+    pub answer: Result<String, PartFailure>,
+    /// Time until the part answered or failed.
+    pub time: Stats,
+    /// Only counted with `--alloc`.
+    pub alloc: Option<AllocStats>,
+    pub verdict: Option<Verdict>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartFailure {
+    /// Still running when its time budget ran out.
+    Timeout(Duration),
+    Panic,
+}
+
+impl Display for PartFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout(budget) => write!(f, "TIMEOUT after {}", format_duration(*budget)),
+            Self::Panic => write!(f, "PANIC"),
+        }
+    }
+}
+
+pub enum DayError {
+    MissingInput,
+    UnreadableInput(String),
+    Answers(String),
+    Parse(ParseError),
+}
+
+impl DayReport {
+    pub fn parse_time(&self) -> Duration {
+        self.result
+            .as_ref()
+            .map_or(Duration::ZERO, |r| r.parse_time.median)
+    }
+
+    /// Time spent solving, excluding parsing. Medians when benchmarking.
+    pub fn runtime(&self) -> Duration {
+        self.result.as_ref().map_or(Duration::ZERO, |r| {
+            r.parts.iter().map(|p| p.time.median).sum()
+        })
+    }
+
+    /// A part timed out or panicked, a checked answer was wrong, the input
+    /// did not parse or the day could not be checked at all.
+    pub fn failed(&self) -> bool {
+        match &self.result {
+            Ok(results) => results
+                .parts
+                .iter()
+                .any(|p| p.answer.is_err() || matches!(p.verdict, Some(Verdict::Fail(_)))),
+            Err(DayError::Answers(_) | DayError::Parse(_)) => true,
+            Err(_) => false,
+        }
+    }
+
+    /// `Day N`, followed by the implementation unless it is the default.
+    fn title(&self) -> String {
+        if self.implementation == DEFAULT_IMPLEMENTATION {
+            format!("Day {}", self.day)
+        } else {
+            format!("Day {} ({})", self.day, self.implementation)
+        }
+    }
+
+    pub fn text(&self, bench: bool) -> String {
+        let (year, day) = (self.year, self.day);
+        let timing = |stats: &Stats, alloc: &Option<AllocStats>| {
+            let time = if bench {
+                stats.to_string()
+            } else {
+                format_duration(stats.median)
+            };
+            match alloc {
+                Some(alloc) => format!("{} ({})", time, alloc),
+                None => time,
+            }
+        };
+
+        let mut out = String::new();
+        let results = match &self.result {
+            Err(DayError::MissingInput) => {
+                let _ = writeln!(out, "Skipping {} day {}, input file missing.", year, day);
+                return out;
+            }
+            Err(DayError::UnreadableInput(e)) => {
+                let _ = writeln!(
+                    out,
+                    "Skipping {} day {}, cannot read input: {}",
+                    year, day, e
+                );
+                return out;
+            }
+            Err(DayError::Answers(e)) => {
+                let _ = writeln!(out, "{}\n  Cannot load answers: {}", self.title(), e);
+                return out;
+            }
+            Err(DayError::Parse(e)) => {
+                let _ = writeln!(out, "{}\n  Parse error: {}", self.title(), e);
+                return out;
+            }
+            Ok(results) => results,
+        };
+
+        let _ = writeln!(out, "{}", self.title());
+        let _ = writeln!(
+            out,
+            "  Parse: {}",
+            timing(&results.parse_time, &results.parse_alloc)
+        );
+        for part in results.parts.iter() {
+            let answer = match &part.answer {
+                Ok(answer) => answer,
+                Err(failure) => {
+                    let _ = writeln!(out, "  Part {}: {}", part.part, failure);
+                    continue;
+                }
+            };
+            let _ = match &part.verdict {
+                Some(verdict) => writeln!(out, "  Part {}: {} [{}]", part.part, answer, verdict),
+                None => writeln!(out, "  Part {}: {}", part.part, answer),
+            };
+            let _ = writeln!(out, "    Runtime: {}", timing(&part.time, &part.alloc));
+        }
+        out
+    }
+
+    /// One record per selected part, for machine-readable output.
+    pub fn records(&self) -> Vec<Record> {
+        let record = |part: u32| Record {
+            year: self.year,
+            day: self.day,
+            implementation: self.implementation,
+            part,
+            answer: None,
+            status: "",
+            parse_ns: None,
+            solve_ns: None,
+            message: None,
+        };
+
+        match &self.result {
+            Ok(results) => results
+                .parts
+                .iter()
+                .map(|p| match &p.answer {
+                    Ok(answer) => Record {
+                        answer: Some(answer.clone()),
+                        status: match p.verdict {
+                            None => "ok",
+                            Some(Verdict::Pass) => "pass",
+                            Some(Verdict::Fail(_)) => "fail",
+                            Some(Verdict::Unknown) => "unknown",
+                        },
+                        parse_ns: Some(results.parse_time.median.as_nanos()),
+                        solve_ns: Some(p.time.median.as_nanos()),
+                        ..record(p.part)
+                    },
+                    Err(failure) => Record {
+                        status: match failure {
+                            PartFailure::Timeout(_) => "timeout",
+                            PartFailure::Panic => "panic",
+                        },
+                        parse_ns: Some(results.parse_time.median.as_nanos()),
+                        message: Some(failure.to_string()),
+                        ..record(p.part)
+                    },
+                })
+                .collect(),
+            Err(e) => {
+                let (status, message) = match e {
+                    DayError::MissingInput => ("skipped", None),
+                    DayError::UnreadableInput(e) => ("skipped", Some(e.clone())),
+                    DayError::Answers(e) => ("error", Some(e.clone())),
+                    DayError::Parse(e) => ("parse_error", Some(e.to_string())),
+                };
+                self.parts
+                    .iter()
+                    .map(|p| Record {
+                        status,
+                        message: message.clone(),
+                        ..record(*p)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Prints day reports as text under a header per year, followed by totals.
+pub struct TextPrinter {
+    bench: bool,
+    day_totals: bool,
+    year: Option<u32>,
+    parse_time: Duration,
+    runtime: Duration,
+}
+
+impl TextPrinter {
+    pub fn new(bench: bool, day_totals: bool) -> Self {
+        Self {
+            bench,
+            day_totals,
+            year: None,
+            parse_time: Duration::ZERO,
+            runtime: Duration::ZERO,
+        }
+    }
+
+    pub fn print(&mut self, report: &DayReport) {
+        if self.year != Some(report.year) {
+            self.finish_year();
+            println!("==== {} ====", report.year);
+            self.year = Some(report.year);
+        }
+
+        print!("{}", report.text(self.bench));
+        if self.day_totals && report.result.is_ok() {
+            let total = report.parse_time() + report.runtime();
+            println!("  Day total: {}", format_duration(total));
+        }
+        self.parse_time += report.parse_time();
+        self.runtime += report.runtime();
+    }
+
+    /// Prints the totals of the current year, if any day was printed.
+    pub fn finish_year(&mut self) {
+        if self.year.take().is_some() {
+            println!();
+            println!("Total parse time: {}", format_duration(self.parse_time));
+            println!("Total runtime: {}", format_duration(self.runtime));
+            self.parse_time = Duration::ZERO;
+            self.runtime = Duration::ZERO;
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Record {
+    pub year: u32,
+    pub day: u32,
+    pub implementation: &'static str,
+    pub part: u32,
+    pub answer: Option<String>,
+    /// `ok`, `pass`, `fail`, `unknown`, `timeout`, `panic`, `skipped`,
+    /// `error` or `parse_error`.
+    pub status: &'static str,
+    pub parse_ns: Option<u128>,
+    pub solve_ns: Option<u128>,
+    pub message: Option<String>,
+}
+
+pub fn to_json(records: &[Record]) -> String {
+    serde_json::to_string_pretty(records).expect("records are serializable")
+}
+
+pub fn to_csv(records: &[Record]) -> String {
+    let mut out =
+        String::from("year,day,implementation,part,answer,status,parse_ns,solve_ns,message\n");
+    for r in records {
+        let optional = |v: Option<u128>| v.map(|v| v.to_string()).unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{}",
+            r.year,
+            r.day,
+            r.implementation,
+            r.part,
+            csv_field(r.answer.as_deref().unwrap_or_default()),
+            r.status,
+            optional(r.parse_ns),
+            optional(r.solve_ns),
+            csv_field(r.message.as_deref().unwrap_or_default()),
+        );
+    }
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Reports to feed the code that reads them.
+#[cfg(test)]
+pub mod fixture {
+    use super::*;
+
+    /// A 2022 report for `day` that parsed in `parse_us` microseconds and
+    /// gave `answers` for parts 1 onwards, each in `part_us` microseconds.
+    pub fn report(
+        day: u32,
+        implementation: &'static str,
+        answers: Vec<Result<&str, PartFailure>>,
+        parse_us: u64,
+        part_us: u64,
+    ) -> DayReport {
+        let stats = |us| Stats::from_samples(&[Duration::from_micros(us)]);
+        let parts: Vec<_> = answers
+            .into_iter()
+            .zip(1..)
+            .map(|(answer, part)| PartReport {
+                part,
+                answer: answer.map(String::from),
+                time: stats(part_us),
+                alloc: None,
+                verdict: None,
+            })
+            .collect();
+        DayReport {
+            year: 2022,
+            day,
+            implementation,
+            parts: parts.iter().map(|p| p.part).collect(),
+            checked: false,
+            result: Ok(DayResults {
+                parse_time: stats(parse_us),
+                parse_alloc: None,
+                parts,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixture::report;
+    use super::*;
+
+    #[test]
+    fn records_for_answers_and_errors() {
+        let answers = vec![Ok("13140"), Ok("\n#.\n.#")];
+        let ran = report(10, DEFAULT_IMPLEMENTATION, answers, 2, 15);
+        assert_eq!(
+            to_csv(&ran.records()),
+            "year,day,implementation,part,answer,status,parse_ns,solve_ns,message\n\
+             2022,10,default,1,13140,ok,2000,15000,\n\
+             2022,10,default,2,\"\n#.\n.#\",ok,2000,15000,\n"
+        );
+
+        let skipped = DayReport {
+            result: Err(DayError::MissingInput),
+            ..report(10, DEFAULT_IMPLEMENTATION, vec![Ok(""), Ok("")], 0, 0)
+        };
+        let records = skipped.records();
+        assert_eq!(records.len(), 2);
+        assert!(to_json(&records).contains("\"status\": \"skipped\""));
+        assert!(!skipped.failed());
+
+        let unparsed = DayReport {
+            result: Err(DayError::Parse(ParseError::new(3, 1, "expected a number"))),
+            ..ran
+        };
+        assert!(unparsed.failed());
+    }
+}
@@ -0,0 +1,96 @@
+//! Runs every sample under `samples/{year}/` that has an `.expected`
+//! sidecar against every registered implementation of its day. A sample `day09-2` with
+//! `day09-2.expected` containing
+//!
+//! ```toml
+//! part2 = 36
+//! ```
+//!
+//! is checked without writing any Rust.
+
+use crate::answers::{DayAnswers, Verdict};
+use crate::harness::DynHarness;
+use crate::params::Params;
+use crate::registry;
+use std::fs;
+use std::path::Path;
+
+/// Year and day of a sample file named like `samples/2022/day09-2`.
+fn sample_day(path: &Path) -> Option<(u32, u32)> {
+    let year = path.parent()?.file_name()?.to_str()?.parse().ok()?;
+    let name = path.file_name()?.to_str()?;
+    let (day, _) = name.strip_prefix("day")?.split_once('-')?;
+    Some((year, day.parse().ok()?))
+}
+
+fn check_sample(path: &Path) -> Result<usize, String> {
+    let (year, day) = sample_day(path).ok_or("unrecognized sample name")?;
+    let solutions: Vec<_> = registry::implementations()
+        .filter(|s| (s.year(), s.day()) == (year, day))
+        .collect();
+    if solutions.is_empty() {
+        return Err(String::from("no registered solution"));
+    }
+    let expected_path = path.with_extension("expected");
+    let raw_expected = fs::read_to_string(&expected_path).map_err(|e| e.to_string())?;
+    let expected: DayAnswers = toml::from_str(&raw_expected).map_err(|e| e.to_string())?;
+    let raw = fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+    let mut checked = 0;
+    for solution in solutions {
+        checked += check_solution(solution, &raw, &expected)
+            .map_err(|e| format!("{}: {}", solution.name(), e))?;
+    }
+    Ok(checked)
+}
+
+fn check_solution(
+    solution: &dyn DynHarness,
+    raw: &str,
+    expected: &DayAnswers,
+) -> Result<usize, String> {
+    let input = solution.parse(raw.to_string()).map_err(|e| e.to_string())?;
+
+    let mut checked = 0;
+    for part in [1, 2] {
+        if expected.expected(part).is_none() {
+            continue;
+        }
+        let answer = match part {
+            1 => solution.part1(input.as_ref(), &Params::default()),
+            _ => solution.part2(input.as_ref(), &Params::default()),
+        };
+        if let Verdict::Fail(e) = expected.verdict(part, &answer.to_string()) {
+            return Err(format!(
+                "part {}: expected {:?}, got {:?}",
+                part,
+                e,
+                answer.to_string()
+            ));
+        }
+        checked += 1;
+    }
+    Ok(checked)
+}
+
+#[test]
+fn all_samples_with_expected_answers() {
+    let mut checked = 0;
+    let mut failures = Vec::new();
+
+    for year in fs::read_dir("samples").unwrap() {
+        for sample in fs::read_dir(year.unwrap().path()).unwrap() {
+            let path = sample.unwrap().path();
+            if path.extension().is_some() || !path.with_extension("expected").exists() {
+                continue;
+            }
+            match check_sample(&path) {
+                Ok(parts) => checked += parts,
+                Err(e) => failures.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+    assert!(checked > 0, "no samples with expected answers found");
+}
@@ -0,0 +1,248 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DAY_TEMPLATE: &str = r#"use crate::harness::Harness;
+use crate::parse::{lines, ParseError};
+
+pub struct Solution;
+
+impl Harness for Solution {
+    type Parsed = Vec<String>;
+    type Part1Output = u32;
+    type Part2Output = u32;
+
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        Ok(lines(&raw_input).map(|l| l.text.to_string()).collect())
+    }
+
+    fn part1(&self, _input: &Self::Parsed) -> Self::Part1Output {
+        todo!()
+    }
+
+    fn part2(&self, _input: &Self::Parsed) -> Self::Part2Output {
+        todo!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::read_to_string;
+
+    #[test]
+    #[ignore = "fill in the sample and its answer"]
+    fn part1_sample1() {
+        let s = Solution {};
+        let raw = read_to_string("samples/{year}/day{day}-1").unwrap();
+        let input = s.parse(raw).unwrap();
+        assert_eq!(s.part1(&input), 0);
+    }
+
+    #[test]
+    #[ignore = "fill in the sample and its answer"]
+    fn part2_sample1() {
+        let s = Solution {};
+        let raw = read_to_string("samples/{year}/day{day}-1").unwrap();
+        let input = s.parse(raw).unwrap();
+        assert_eq!(s.part2(&input), 0);
+    }
+}
+"#;
+
+/// Generates the module for a new day under the source tree at `root`:
+/// the solution file, its entry in the year's `register_days!`, an empty
+/// sample, and the year module itself if this is the year's first day.
+/// Returns the files created or changed.
+pub fn new_day(root: &Path, year: u32, day: u32) -> Result<Vec<PathBuf>, String> {
+    let module = format!("day{:02}", day);
+    let year_dir = root.join(format!("src/year{}", year));
+    let day_path = year_dir.join(format!("{}.rs", module));
+    let mod_path = year_dir.join("mod.rs");
+    let sample_path = root.join(format!("samples/{}/{}-1", year, module));
+    if day_path.exists() {
+        return Err(format!("{} already exists", day_path.display()));
+    }
+
+    // Work out every edit before writing, so a failure leaves no half-added day.
+    let mut files = Vec::new();
+    if mod_path.exists() {
+        let source = read(&mod_path)?;
+        files.push((mod_path, register_day(&source, day, &module)?));
+    } else {
+        let lib_path = root.join("src/lib.rs");
+        let lib = declare_year(&read(&lib_path)?, year)?;
+        let registry_path = root.join("src/registry.rs");
+        let registry = register_year(&read(&registry_path)?, year)?;
+        files.push((lib_path, lib));
+        files.push((registry_path, registry));
+        files.push((
+            mod_path,
+            format!(
+                "crate::register_days! {{\n    {};\n    {} => {},\n}}\n",
+                year, day, module
+            ),
+        ));
+    }
+    files.push((
+        day_path,
+        DAY_TEMPLATE
+            .replace("{year}", &year.to_string())
+            .replace("{day}", &format!("{:02}", day)),
+    ));
+    if !sample_path.exists() {
+        files.push((sample_path, String::new()));
+    }
+
+    for (path, contents) in &files {
+        create_dir(path.parent().expect("generated files have a parent"))?;
+        write(path, contents)?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Inserts `day => module,` into the `register_days!` invocation in
+/// `source`, keeping the days sorted.
+fn register_day(source: &str, day: u32, module: &str) -> Result<String, String> {
+    let entry = |line: &str| -> Option<u32> {
+        let (day, _) = line.trim().split_once("=>")?;
+        day.trim().parse().ok()
+    };
+
+    let mut lines: Vec<&str> = source.lines().collect();
+    let mut insert_at = None;
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(existing) = entry(line) {
+            if existing == day {
+                return Err(format!("day {} is already registered", day));
+            }
+            if existing < day {
+                insert_at = Some(i + 1);
+            } else if insert_at.is_none() {
+                insert_at = Some(i);
+            }
+        }
+    }
+
+    let new_line = format!("    {} => {},", day, module);
+    let at = insert_at.ok_or("no register_days! entries found")?;
+    lines.insert(at, &new_line);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds `mod yearYYYY;` after the last year module declared before it.
+fn declare_year(lib: &str, year: u32) -> Result<String, String> {
+    let declaration = format!("mod year{};", year);
+    insert_sorted(lib, &declaration, |line| {
+        line.starts_with("mod year").then(|| line.to_string())
+    })
+    .ok_or_else(|| String::from("no year modules declared in lib.rs"))
+}
+
+/// Adds the new year's solutions to the registry's list of years, before
+/// the first later year or after the last earlier one.
+fn register_year(registry: &str, year: u32) -> Result<String, String> {
+    const PREFIX: &str = "crate::year";
+    let entry = format!("{}{}::SOLUTIONS", PREFIX, year);
+    let years: Vec<(usize, u32)> = registry
+        .match_indices(PREFIX)
+        .filter_map(|(at, _)| {
+            let digits = registry[at + PREFIX.len()..].get(..4)?;
+            Some((at, digits.parse().ok()?))
+        })
+        .collect();
+
+    let mut registry = registry.to_string();
+    match years.iter().find(|(_, y)| *y >= year) {
+        Some((_, y)) if *y == year => return Err(format!("year {} is already registered", year)),
+        Some((at, _)) => registry.insert_str(*at, &format!("{}, ", entry)),
+        None => {
+            let (at, _) = years.last().ok_or("no years listed in registry.rs")?;
+            let end = at + entry.len();
+            registry.insert_str(end, &format!(", {}", entry));
+        }
+    }
+    Ok(registry)
+}
+
+/// Inserts `new_line` among the lines for which `key` returns a value, in
+/// sorted position. `None` if there are no such lines.
+fn insert_sorted<F>(source: &str, new_line: &str, key: F) -> Option<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut lines: Vec<&str> = source.lines().collect();
+    let mut insert_at = None;
+    for (i, line) in lines.iter().enumerate() {
+        match key(line) {
+            Some(k) if k.as_str() < new_line => insert_at = Some(i + 1),
+            Some(_) if insert_at.is_none() => insert_at = Some(i),
+            _ => {}
+        }
+    }
+    lines.insert(insert_at?, new_line);
+    Some(lines.join("\n") + "\n")
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn create_dir(path: &Path) -> Result<(), String> {
+    fs::create_dir_all(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaffolds_days_and_years() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/year2022")).unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "pub mod registry;\nmod year2022;\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/registry.rs"),
+            "static YEARS: &[&[&dyn DynHarness]] = &[crate::year2022::SOLUTIONS];\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/year2022/mod.rs"),
+            "crate::register_days! {\n    2022;\n    1 => day01,\n    3 => day03,\n}\n",
+        )
+        .unwrap();
+
+        new_day(&root, 2022, 2).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("src/year2022/mod.rs")).unwrap(),
+            "crate::register_days! {\n    2022;\n    1 => day01,\n    2 => day02,\n    3 => day03,\n}\n"
+        );
+        let day = fs::read_to_string(root.join("src/year2022/day02.rs")).unwrap();
+        assert!(day.contains("samples/2022/day02-1"));
+        assert!(root.join("samples/2022/day02-1").exists());
+        assert!(new_day(&root, 2022, 2).is_err());
+
+        new_day(&root, 2015, 1).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+            "pub mod registry;\nmod year2015;\nmod year2022;\n"
+        );
+        assert!(fs::read_to_string(root.join("src/registry.rs"))
+            .unwrap()
+            .contains("&[crate::year2015::SOLUTIONS, crate::year2022::SOLUTIONS]"));
+        new_day(&root, 2023, 1).unwrap();
+        assert!(fs::read_to_string(root.join("src/registry.rs"))
+            .unwrap()
+            .contains("crate::year2022::SOLUTIONS, crate::year2023::SOLUTIONS]"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,341 @@
+use crate::client::Client;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What the site said about a submitted answer.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after the previous answer; nothing was checked.
+    RateLimited,
+    /// The part is locked or was already solved; nothing was checked.
+    WrongLevel,
+}
+
+impl Outcome {
+    fn is_wrong(self) -> bool {
+        matches!(self, Self::Incorrect | Self::TooHigh | Self::TooLow)
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::Incorrect => write!(f, "incorrect"),
+            Self::TooHigh => write!(f, "incorrect, too high"),
+            Self::TooLow => write!(f, "incorrect, too low"),
+            Self::RateLimited => write!(f, "rate limited"),
+            Self::WrongLevel => write!(f, "not the right level; already solved or locked"),
+        }
+    }
+}
+
+/// Reads the outcome and the imposed wait in seconds, if any, from the
+/// site's response page.
+pub fn parse_response(html: &str) -> Option<(Outcome, Option<u64>)> {
+    let outcome = if html.contains("That's the right answer") {
+        Outcome::Correct
+    } else if html.contains("You gave an answer too recently") {
+        Outcome::RateLimited
+    } else if html.contains("You don't seem to be solving the right level") {
+        Outcome::WrongLevel
+    } else if html.contains("That's not the right answer") {
+        if html.contains("your answer is too high") {
+            Outcome::TooHigh
+        } else if html.contains("your answer is too low") {
+            Outcome::TooLow
+        } else {
+            Outcome::Incorrect
+        }
+    } else {
+        return None;
+    };
+    Some((outcome, parse_wait(html)))
+}
+
+/// Understands "You have 1m 30s left to wait" and "please wait one minute"
+/// or "please wait 5 minutes".
+fn parse_wait(html: &str) -> Option<u64> {
+    let html = html.to_ascii_lowercase();
+    if let Some(at) = html.find(" left to wait") {
+        let (_, wait) = html[..at].rsplit_once("you have ")?;
+        return wait.split_whitespace().try_fold(0, |total, part| {
+            let (number, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+            let number: u64 = number.parse().ok()?;
+            match unit {
+                "h" => Some(total + number * 3600),
+                "m" => Some(total + number * 60),
+                "s" => Some(total + number),
+                _ => None,
+            }
+        });
+    }
+
+    let (_, wait) = html.split_once("please wait ")?;
+    let (amount, rest) = wait.split_once(' ')?;
+    if !rest.starts_with("minute") {
+        return None;
+    }
+    let minutes = match amount {
+        "one" => 1,
+        amount => amount.parse().ok()?,
+    };
+    Some(minutes * 60)
+}
+
+/// One submitted answer, as recorded in the submission log.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub outcome: Outcome,
+    /// Seconds since the Unix epoch.
+    pub submitted_at: u64,
+    /// Seconds the site asked to wait before the next submission.
+    pub wait: Option<u64>,
+}
+
+/// Every answer submitted so far, one JSON object per line in
+/// `answers/submissions.jsonl`.
+pub struct SubmissionLog {
+    path: PathBuf,
+    pub submissions: Vec<Submission>,
+}
+
+impl SubmissionLog {
+    pub const PATH: &'static str = "answers/submissions.jsonl";
+
+    /// Loads the log at `path`; a missing file is an empty log.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let raw = match std::fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        };
+        let submissions = raw
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| format!("{}: line {}: {}", path.display(), i + 1, e))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            submissions,
+        })
+    }
+
+    pub fn append(&mut self, submission: Submission) -> Result<(), String> {
+        let error = |e: std::io::Error| format!("{}: {}", self.path.display(), e);
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(error)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(error)?;
+        let line = serde_json::to_string(&submission).expect("submissions are serializable");
+        writeln!(file, "{}", line).map_err(error)?;
+        self.submissions.push(submission);
+        Ok(())
+    }
+
+    /// Explains why `answer` should not be submitted: the part is already
+    /// solved, the answer is known to be wrong, or the site asked us to wait.
+    pub fn refusal(
+        &self,
+        year: u32,
+        day: u32,
+        part: u32,
+        answer: &str,
+        now: u64,
+    ) -> Option<String> {
+        let mut previous = self
+            .submissions
+            .iter()
+            .filter(|s| (s.year, s.day, s.part) == (year, day, part));
+        let numeric = answer.parse::<i64>().ok();
+        let known_wrong = |s: &Submission| {
+            let bound = s.answer.parse::<i64>().ok();
+            match (s.outcome, numeric, bound) {
+                _ if s.outcome.is_wrong() && s.answer == answer => true,
+                (Outcome::TooHigh, Some(answer), Some(high)) => answer >= high,
+                (Outcome::TooLow, Some(answer), Some(low)) => answer <= low,
+                _ => false,
+            }
+        };
+
+        if let Some(s) = previous.clone().find(|s| s.outcome == Outcome::Correct) {
+            return Some(format!("Already solved, the answer was {}", s.answer));
+        }
+        if let Some(s) = previous.find(|s| known_wrong(s)) {
+            return Some(format!(
+                "{} is known to be wrong: {} was {}",
+                answer, s.answer, s.outcome
+            ));
+        }
+
+        let wait_until = self
+            .submissions
+            .iter()
+            .filter_map(|s| Some(s.submitted_at + s.wait?))
+            .max()?;
+        (wait_until > now)
+            .then(|| format!("Submitted too recently, wait another {}s", wait_until - now))
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Submits `answer` unless the log says not to, and records the outcome.
+pub fn submit(
+    client: &mut Client,
+    log: &mut SubmissionLog,
+    (year, day, part): (u32, u32, u32),
+    answer: &str,
+) -> Result<Outcome, String> {
+    let now = now();
+    if let Some(refusal) = log.refusal(year, day, part, answer, now) {
+        return Err(refusal);
+    }
+
+    let path = format!("/{}/day/{}/answer", year, day);
+    let level = part.to_string();
+    let html = client.post_form(&path, &[("level", &level), ("answer", answer)])?;
+    let (outcome, wait) =
+        parse_response(&html).ok_or_else(|| format!("{}: unrecognized response", path))?;
+
+    log.append(Submission {
+        year,
+        day,
+        part,
+        answer: answer.to_string(),
+        outcome,
+        submitted_at: now,
+        wait,
+    })?;
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{mock, ClientConfig};
+    use std::time::Duration;
+
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high. \
+        If you're stuck, make sure you're using the full input data. \
+        Please wait one minute before trying again. [<a href=\"/2022/day/11\">Return to Day 11</a>]</p></article>";
+    const TOO_RECENT: &str = "<article><p>You gave an answer too recently; you have to wait after \
+        submitting an answer before trying again.  You have 1m 5s left to wait.</p></article>";
+    const CORRECT: &str =
+        "<article><p>That's the right answer!  You are one gold star closer.</p></article>";
+
+    fn submission(answer: &str, outcome: Outcome, wait: Option<u64>) -> Submission {
+        Submission {
+            year: 2022,
+            day: 11,
+            part: 1,
+            answer: answer.to_string(),
+            outcome,
+            submitted_at: 1000,
+            wait,
+        }
+    }
+
+    #[test]
+    fn responses() {
+        assert_eq!(parse_response(TOO_HIGH), Some((Outcome::TooHigh, Some(60))));
+        assert_eq!(
+            parse_response(TOO_RECENT),
+            Some((Outcome::RateLimited, Some(65)))
+        );
+        assert_eq!(parse_response(CORRECT), Some((Outcome::Correct, None)));
+        assert_eq!(
+            parse_response(
+                "That's not the right answer. Please wait 5 minutes before trying again."
+            ),
+            Some((Outcome::Incorrect, Some(300)))
+        );
+        assert_eq!(
+            parse_response("<html>Puzzle inputs differ by user.</html>"),
+            None
+        );
+    }
+
+    #[test]
+    fn refuses_known_wrong_answers() {
+        let log = SubmissionLog {
+            path: PathBuf::new(),
+            submissions: vec![
+                submission("500", Outcome::TooHigh, Some(60)),
+                submission("20", Outcome::TooLow, None),
+                submission("abc", Outcome::Incorrect, None),
+            ],
+        };
+        assert!(log
+            .refusal(2022, 11, 1, "100", 1030)
+            .unwrap()
+            .contains("wait another 30s"));
+        assert_eq!(log.refusal(2022, 11, 1, "100", 1060), None);
+        assert!(log
+            .refusal(2022, 11, 1, "600", 1060)
+            .unwrap()
+            .contains("500 was incorrect, too high"));
+        assert!(log.refusal(2022, 11, 1, "20", 1060).is_some());
+        assert!(log.refusal(2022, 11, 1, "abc", 1060).is_some());
+        assert_eq!(log.refusal(2022, 11, 2, "600", 1060), None);
+    }
+
+    #[test]
+    fn submits_and_logs() {
+        let (base_url, server) = mock::serve(vec![(200, TOO_HIGH), (200, CORRECT)]);
+        let mut client = Client::new(ClientConfig {
+            session: Some(String::from("abc")),
+            base_url,
+            min_interval: Duration::ZERO,
+        });
+        let dir = std::env::temp_dir().join(format!("aoc-submit-{}", std::process::id()));
+        let path = dir.join("submissions.jsonl");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut log = SubmissionLog::load(&path).unwrap();
+        let outcome = submit(&mut client, &mut log, (2022, 11, 1), "500");
+        assert_eq!(outcome, Ok(Outcome::TooHigh));
+        assert!(submit(&mut client, &mut log, (2022, 11, 1), "500").is_err());
+        // The site asked for a minute's wait; pretend it has passed.
+        log.submissions[0].submitted_at -= 60;
+        let outcome = submit(&mut client, &mut log, (2022, 11, 1), "450");
+        assert_eq!(outcome, Ok(Outcome::Correct));
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[0].request_line,
+            "POST /2022/day/11/answer HTTP/1.1"
+        );
+        assert_eq!(requests[1].body, "level=1&answer=450");
+
+        let reloaded = SubmissionLog::load(&path).unwrap();
+        assert_eq!(reloaded.submissions.len(), 2);
+        assert_eq!(reloaded.submissions[1].outcome, Outcome::Correct);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt::{self, Display};
+use std::time::{Duration, Instant};
+
+pub fn measure<F, O>(f: F) -> (O, Duration)
+where
+    F: FnOnce() -> O,
+{
+    let start = Instant::now();
+    let result = f();
+    let end = Instant::now();
+    (result, end - start)
+}
+
+/// Formats `d` in the largest unit it has more than one of.
+pub fn format_duration(d: Duration) -> String {
+    if d.as_millis() > 1 {
+        format!("{} ms", d.as_millis())
+    } else if d.as_micros() > 1 {
+        format!("{} \u{00b5}s", d.as_micros())
+    } else {
+        format!("{} ns", d.as_nanos())
+    }
+}
+
+/// Summary of repeated timings of the same operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Panics if `samples` is empty.
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let runs = sorted.len();
+        let median = if runs.is_multiple_of(2) {
+            (sorted[runs / 2 - 1] + sorted[runs / 2]) / 2
+        } else {
+            sorted[runs / 2]
+        };
+
+        let mean = sorted.iter().sum::<Duration>() / runs as u32;
+        let variance = sorted
+            .iter()
+            .map(|s| (s.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / runs as f64;
+
+        Self {
+            runs,
+            min: sorted[0],
+            median,
+            mean,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {}, median {}, mean {}, stddev {} ({} runs)",
+            format_duration(self.min),
+            format_duration(self.median),
+            format_duration(self.mean),
+            format_duration(self.stddev),
+            self.runs
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let samples: Vec<_> = [4, 1, 3, 2]
+            .into_iter()
+            .map(Duration::from_micros)
+            .collect();
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.min, Duration::from_micros(1));
+        assert_eq!(stats.median, Duration::from_nanos(2500));
+        assert_eq!(stats.mean, Duration::from_nanos(2500));
+        assert_eq!(stats.stddev.as_nanos(), 1118);
+
+        let single = Stats::from_samples(&[Duration::from_millis(3)]);
+        assert_eq!(single.median, Duration::from_millis(3));
+        assert_eq!(single.stddev, Duration::ZERO);
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(format_duration(Duration::from_millis(12)), "12 ms");
+        assert_eq!(
+            format_duration(Duration::from_micros(1500)),
+            "1500 \u{00b5}s"
+        );
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999 ns");
+    }
+}
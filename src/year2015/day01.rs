@@ -1,4 +1,5 @@
 use crate::harness::Harness;
+use crate::parse::{lines, ParseError};
 
 pub struct Solution;
 
@@ -7,8 +8,17 @@ impl Harness for Solution {
     type Part1Output = i32;
     type Part2Output = usize;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        raw_input.into_bytes()
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        let mut parsed = Vec::new();
+        for line in lines(&raw_input) {
+            for (i, b) in line.text.bytes().enumerate() {
+                match b {
+                    b'(' | b')' => parsed.push(b),
+                    _ => return Err(line.error_at(i, "expected `(` or `)`")),
+                }
+            }
+        }
+        Ok(parsed)
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
@@ -1,5 +1,5 @@
 use crate::harness::Harness;
-use std::str::FromStr;
+use crate::parse::{lines, Line, ParseError};
 
 pub struct Solution;
 
@@ -8,8 +8,8 @@ impl Harness for Solution {
     type Part1Output = u32;
     type Part2Output = u32;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        raw_input.lines().map(|l| l.parse().unwrap()).collect()
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        lines(&raw_input).map(|l| Box::parse(&l)).collect()
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
@@ -46,14 +46,12 @@ impl Box {
     }
 }
 
-impl FromStr for Box {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.splitn(3, 'x');
-        let length = parts.next().unwrap().parse().unwrap();
-        let width = parts.next().unwrap().parse().unwrap();
-        let height = parts.next().unwrap().parse().unwrap();
+impl Box {
+    fn parse(line: &Line) -> Result<Self, ParseError> {
+        let mut parts = line.text.splitn(3, 'x');
+        let length = line.parse(line.expect(&mut parts, "length")?)?;
+        let width = line.parse(line.expect(&mut parts, "width")?)?;
+        let height = line.parse(line.expect(&mut parts, "height")?)?;
         Ok(Self {
             length,
             width,
@@ -1,4 +1,5 @@
 use crate::harness::Harness;
+use crate::parse::{lines, ParseError};
 use std::collections::HashSet;
 
 pub struct Solution;
@@ -8,8 +9,16 @@ impl Harness for Solution {
     type Part1Output = usize;
     type Part2Output = usize;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        raw_input.into_bytes().iter().map(|b| (*b).into()).collect()
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        let mut parsed = Vec::new();
+        for line in lines(&raw_input) {
+            for (i, b) in line.text.bytes().enumerate() {
+                let direction = Direction::from_byte(b)
+                    .ok_or_else(|| line.error_at(i, "expected one of `^v<>`"))?;
+                parsed.push(direction);
+            }
+        }
+        Ok(parsed)
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
@@ -61,16 +70,14 @@ impl Direction {
             Direction::Right => (location.0 + 1, location.1),
         }
     }
-}
 
-impl From<u8> for Direction {
-    fn from(b: u8) -> Self {
+    fn from_byte(b: u8) -> Option<Self> {
         match b {
-            b'^' => Self::Up,
-            b'v' => Self::Down,
-            b'<' => Self::Left,
-            b'>' => Self::Right,
-            _ => unreachable!(),
+            b'^' => Some(Self::Up),
+            b'v' => Some(Self::Down),
+            b'<' => Some(Self::Left),
+            b'>' => Some(Self::Right),
+            _ => None,
         }
     }
 }
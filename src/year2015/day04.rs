@@ -1,4 +1,5 @@
 use crate::harness::Harness;
+use crate::parse::ParseError;
 use md5::{Digest, Md5};
 
 pub struct Solution;
@@ -8,8 +9,8 @@ impl Harness for Solution {
     type Part1Output = u32;
     type Part2Output = u32;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        raw_input
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        Ok(raw_input)
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
@@ -1,4 +1,7 @@
 use crate::harness::Harness;
+use crate::parse::{lines, ParseError};
+use rand::rngs::StdRng;
+use rand::Rng;
 use std::cmp::Reverse;
 
 pub struct Solution;
@@ -8,8 +11,13 @@ impl Harness for Solution {
     type Part1Output = i32;
     type Part2Output = i32;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        raw_input.lines().map(|l| l.parse().ok()).collect()
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        lines(&raw_input)
+            .map(|l| match l.text {
+                "" => Ok(None),
+                text => l.parse(text).map(Some),
+            })
+            .collect()
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
@@ -42,4 +50,16 @@ impl Harness for Solution {
         sums.sort_unstable_by_key(|i| Reverse(*i));
         sums.into_iter().take(3).sum()
     }
+
+    /// `size` elves.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        let elves: Vec<String> = (0..size.max(1))
+            .map(|_| {
+                (0..rng.gen_range(1..=15))
+                    .map(|_| format!("{}\n", rng.gen_range(1000..=70_000)))
+                    .collect()
+            })
+            .collect();
+        Some(elves.join("\n"))
+    }
 }
@@ -1,4 +1,7 @@
 use crate::harness::Harness;
+use crate::parse::{lines, ParseError};
+use rand::rngs::StdRng;
+use rand::Rng;
 
 pub struct Solution;
 
@@ -7,15 +10,11 @@ impl Harness for Solution {
     type Part1Output = u32;
     type Part2Output = u32;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        raw_input
-            .lines()
-            .map(|s| {
-                let mut c = s.chars();
-                let c1 = c.next().unwrap();
-                c.next();
-                let c2 = c.next().unwrap();
-                (c1, c2)
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        lines(&raw_input)
+            .map(|l| match l.text.as_bytes() {
+                [c1 @ b'A'..=b'C', b' ', c2 @ b'X'..=b'Z'] => Ok((*c1 as char, *c2 as char)),
+                _ => Err(l.error_at(0, "expected `A|B|C X|Y|Z`")),
             })
             .collect()
     }
@@ -39,6 +38,15 @@ impl Harness for Solution {
         }
         score
     }
+
+    /// `size` rounds.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        let rounds = (0..size).map(|_| {
+            let opponent = rng.gen_range('A'..='C');
+            format!("{} {}\n", opponent, rng.gen_range('X'..='Z'))
+        });
+        Some(rounds.collect())
+    }
 }
 
 enum Play {
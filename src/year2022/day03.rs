@@ -1,4 +1,8 @@
 use crate::harness::Harness;
+use crate::parse::{lines, Line, ParseError};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
 
 pub struct Solution;
 
@@ -7,14 +11,26 @@ impl Harness for Solution {
     type Part1Output = u32;
     type Part2Output = u32;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        raw_input
-            .lines()
-            .map(|s| {
-                let (left, right) = s.split_at(s.len() / 2);
-                (parse(left), parse(right))
-            })
-            .collect()
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        let mut lines = lines(&raw_input);
+        let mut rucksacks = Vec::new();
+        // Part 2 takes the rucksacks in groups of three.
+        while let Some(first) = lines.next() {
+            let mut badges = u64::MAX;
+            for i in 0..3 {
+                let line = match i {
+                    0 => first,
+                    _ => lines.expect("the rest of a group of three rucksacks")?,
+                };
+                let rucksack = parse_rucksack(&line)?;
+                badges &= rucksack.0 | rucksack.1;
+                rucksacks.push(rucksack);
+            }
+            if badges.count_ones() != 1 {
+                return Err(first.error_at(0, "group must share exactly one item"));
+            }
+        }
+        Ok(rucksacks)
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
@@ -27,19 +43,63 @@ impl Harness for Solution {
             .map(|c| unshift((c[0].0 | c[0].1) & (c[1].0 | c[1].1) & (c[2].0 | c[2].1)))
             .sum()
     }
+
+    /// `size` groups of three rucksacks. Each elf packs from its own share
+    /// of the items plus the group's badge, so both the item in both
+    /// compartments and the badge are unique.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        let mut items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+        let mut out = String::new();
+        for _ in 0..size.max(1) {
+            items.shuffle(rng);
+            let (badge, shares) = items.split_first().unwrap();
+            for share in shares.chunks(17) {
+                let (shared, rest) = share.split_first().unwrap();
+                let (left_only, right_only) = rest.split_at(8);
+                let half = rng.gen_range(2..=16);
+                let mut left: Vec<_> = (0..half).map(|_| left_only.choose(rng).unwrap()).collect();
+                let mut right: Vec<_> = (0..half).map(|_| right_only.choose(rng).unwrap()).collect();
+                left[0] = shared;
+                right[0] = shared;
+                if rng.gen() {
+                    left[1] = badge;
+                } else {
+                    right[1] = badge;
+                }
+                left.shuffle(rng);
+                right.shuffle(rng);
+                out.extend(left.into_iter().chain(right));
+                out.push('\n');
+            }
+        }
+        Some(out)
+    }
+}
+
+fn parse_rucksack(line: &Line) -> Result<(u64, u64), ParseError> {
+    // Only letters, so that halving the bytes halves the items.
+    if let Some(i) = line.text.find(|c: char| !c.is_ascii_alphabetic()) {
+        return Err(line.error_at(i, "expected a letter"));
+    }
+    if !line.text.len().is_multiple_of(2) {
+        return Err(line.error_at(line.text.len(), "expected an even number of items"));
+    }
+    let (left, right) = line.text.as_bytes().split_at(line.text.len() / 2);
+    let (left, right) = (items(left), items(right));
+    if (left & right).count_ones() != 1 {
+        return Err(line.error_at(0, "compartments must share exactly one item"));
+    }
+    Ok((left, right))
 }
 
-fn parse(s: &str) -> u64 {
-    let mut x = 0;
-    for c in s.chars() {
+fn items(compartment: &[u8]) -> u64 {
+    compartment.iter().fold(0, |x, &c| {
         let shift = match c {
-            'a'..='z' => (c as u8) - b'a',
-            'A'..='Z' => (c as u8) - b'A' + 26,
-            _ => unreachable!(),
+            b'a'..=b'z' => c - b'a',
+            _ => c - b'A' + 26,
         };
-        x |= 1 << shift;
-    }
-    x
+        x | 1 << shift
+    })
 }
 
 fn unshift(n: u64) -> u32 {
@@ -99,3 +159,33 @@ fn unshift(n: u64) -> u32 {
         _ => unreachable!(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_rucksacks() {
+        let s = Solution {};
+        let err = s.parse(String::from("abcb\ncdcé\nefef\n")).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        let err = s.parse(String::from("abcb\nbdeb\nbfgb\nghih\n")).unwrap_err();
+        assert_eq!((err.line, err.column), (5, 1));
+        assert!(err.message.contains("group of three"));
+
+        for (raw, line, column, message) in [
+            ("abcd\nefgh\nijkl\n", 1, 1, "compartments must share exactly one item"),
+            ("abcb\nbdeb\nbfgbx\n", 3, 6, "expected an even number of items"),
+            ("abcb\ndedf\nghgi\n", 1, 1, "group must share exactly one item"),
+            ("abcb\nabdb\nabeb\n", 1, 1, "group must share exactly one item"),
+        ] {
+            let err = s.parse(String::from(raw)).unwrap_err();
+            assert_eq!(
+                (err.line, err.column, err.message.as_str()),
+                (line, column, message),
+                "{}",
+                raw
+            );
+        }
+    }
+}
@@ -1,4 +1,7 @@
 use crate::harness::Harness;
+use crate::parse::{lines, ParseError};
+use rand::rngs::StdRng;
+use rand::Rng;
 
 pub struct Solution;
 
@@ -7,21 +10,21 @@ impl Harness for Solution {
     type Part1Output = usize;
     type Part2Output = usize;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        raw_input
-            .lines()
-            .map(|s| {
-                let mut ranges = s.split([',', '-']);
-                (
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        lines(&raw_input)
+            .map(|l| {
+                let mut ranges = l.text.split([',', '-']);
+                let mut next = || l.parse(l.expect(&mut ranges, "a section number")?);
+                Ok((
                     Range {
-                        start: ranges.next().unwrap().parse().unwrap(),
-                        end: ranges.next().unwrap().parse().unwrap(),
+                        start: next()?,
+                        end: next()?,
                     },
                     Range {
-                        start: ranges.next().unwrap().parse().unwrap(),
-                        end: ranges.next().unwrap().parse().unwrap(),
+                        start: next()?,
+                        end: next()?,
                     },
-                )
+                ))
             })
             .collect()
     }
@@ -41,6 +44,15 @@ impl Harness for Solution {
             .filter(|(a, b)| a.start <= b.end && a.end >= b.start)
             .count()
     }
+
+    /// `size` pairs.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        let mut range = || {
+            let start = rng.gen_range(1..=99);
+            format!("{}-{}", start, rng.gen_range(start..=99))
+        };
+        Some((0..size).map(|_| format!("{},{}\n", range(), range())).collect())
+    }
 }
 
 pub struct Range {
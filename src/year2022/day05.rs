@@ -1,4 +1,8 @@
 use crate::harness::Harness;
+use crate::parse::{lines, ParseError};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
 
 pub struct Solution;
 
@@ -7,16 +11,16 @@ impl Harness for Solution {
     type Part1Output = String;
     type Part2Output = String;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        let mut lines = raw_input.lines();
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        let mut lines = lines(&raw_input);
 
         let mut stacks = Vec::new();
         for line in &mut lines {
-            if !line.contains('[') {
+            if !line.text.contains('[') {
                 break;
             }
 
-            let crates = line.chars().skip(1).step_by(4);
+            let crates = line.text.chars().skip(1).step_by(4);
             for (i, c) in crates.enumerate() {
                 if c == ' ' {
                     continue;
@@ -36,15 +40,21 @@ impl Harness for Solution {
 
         lines.next(); // Skip blank line
         for line in &mut lines {
-            let mut parts = line.split(' ').skip(1).step_by(2);
-            moves.push(Move {
-                qty: parts.next().unwrap().parse().unwrap(),
-                src: parts.next().unwrap().parse().unwrap(),
-                dst: parts.next().unwrap().parse().unwrap(),
-            });
+            let mut parts = line.text.split(' ').skip(1).step_by(2);
+            let qty = line.parse(line.expect(&mut parts, "a quantity")?)?;
+            let mut stack = || {
+                let token = line.expect(&mut parts, "a stack number")?;
+                match line.parse(token)? {
+                    n @ 1.. if n <= stacks.len() => Ok(n),
+                    _ => Err(line.error(token, format!("no stack {}", token))),
+                }
+            };
+            let src = stack()?;
+            let dst = stack()?;
+            moves.push(Move { qty, src, dst });
         }
 
-        (stacks, moves)
+        Ok((stacks, moves))
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
@@ -85,6 +95,43 @@ impl Harness for Solution {
 
         message
     }
+
+    /// Nine stacks and `size` moves, each taking crates that are there.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        let stacks: Vec<Vec<char>> = (0..9)
+            .map(|_| {
+                (0..rng.gen_range(1..=8))
+                    .map(|_| rng.gen_range('A'..='Z'))
+                    .collect()
+            })
+            .collect();
+
+        let mut out = String::new();
+        let height = stacks.iter().map(Vec::len).max().unwrap();
+        for level in (0..height).rev() {
+            let row: Vec<_> = stacks
+                .iter()
+                .map(|s| s.get(level).map_or(String::from("   "), |c| format!("[{}]", c)))
+                .collect();
+            out += &row.join(" ");
+            out.push('\n');
+        }
+        let numbers: Vec<_> = (1..=stacks.len()).map(|i| format!(" {} ", i)).collect();
+        out += &numbers.join(" ");
+        out += "\n\n";
+
+        let mut heights: Vec<usize> = stacks.iter().map(Vec::len).collect();
+        for _ in 0..size {
+            let sources: Vec<_> = (0..heights.len()).filter(|&i| heights[i] > 0).collect();
+            let src = *sources.choose(rng).unwrap();
+            let dst = (src + rng.gen_range(1..heights.len())) % heights.len();
+            let qty = rng.gen_range(1..=heights[src].min(10));
+            heights[src] -= qty;
+            heights[dst] += qty;
+            out += &format!("move {} from {} to {}\n", qty, src + 1, dst + 1);
+        }
+        Some(out)
+    }
 }
 
 #[derive(Debug)]
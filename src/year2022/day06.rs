@@ -1,39 +1,127 @@
 use crate::harness::Harness;
+use crate::params::{Param, Params};
+use crate::parse::{lines, ParseError};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::fmt::{self, Display};
 
 pub struct Solution;
 
+/// Tracks the window's characters in a bitmask instead of comparing every
+/// pair, so each step costs the same whatever the marker size.
+pub struct Bitmask;
+
+const PACKET_MARKER: Param = Param {
+    name: "packet_marker",
+    default: 4,
+    min: 1,
+    max: 128,
+    help: "distinct characters in a start-of-packet marker",
+};
+const MESSAGE_MARKER: Param = Param {
+    name: "message_marker",
+    default: 14,
+    min: 1,
+    max: 128,
+    help: "distinct characters in a start-of-message marker",
+};
+
 impl Harness for Solution {
     type Parsed = Vec<char>;
-    type Part1Output = usize;
-    type Part2Output = usize;
+    type Part1Output = Marker;
+    type Part2Output = Marker;
+
+    const PARAMS: &'static [Param] = &[PACKET_MARKER, MESSAGE_MARKER];
+
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        for line in lines(&raw_input) {
+            if let Some(i) = line.text.find(|c: char| !c.is_ascii()) {
+                return Err(line.error_at(i, "expected an ASCII character"));
+            }
+        }
+        Ok(raw_input.chars().collect())
+    }
+
+    fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
+        self.part1_with(input, &Params::default())
+    }
+
+    fn part2(&self, input: &Self::Parsed) -> Self::Part2Output {
+        self.part2_with(input, &Params::default())
+    }
+
+    fn part1_with(&self, input: &Self::Parsed, params: &Params) -> Self::Part1Output {
+        Marker(find_marker(input, params.get(&PACKET_MARKER) as usize))
+    }
+
+    fn part2_with(&self, input: &Self::Parsed, params: &Params) -> Self::Part2Output {
+        Marker(find_marker(input, params.get(&MESSAGE_MARKER) as usize))
+    }
+
+    /// A message marker after about `size` characters drawn from only half
+    /// the alphabet, so it cannot turn up any earlier.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        let mut letters: Vec<char> = ('a'..='z').collect();
+        letters.shuffle(rng);
+        let mut signal: String = (0..size)
+            .map(|_| letters[..13].choose(rng).unwrap())
+            .collect();
+        letters.shuffle(rng);
+        signal.extend(&letters[..14]);
+        signal.extend((0..size / 4).map(|_| letters.choose(rng).unwrap()));
+        Some(signal)
+    }
+}
+
+impl Harness for Bitmask {
+    type Parsed = Vec<char>;
+    type Part1Output = Marker;
+    type Part2Output = Marker;
+
+    const PARAMS: &'static [Param] = &[PACKET_MARKER, MESSAGE_MARKER];
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        raw_input.chars().collect()
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        Solution.parse(raw_input)
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
-        const SIZE: usize = 4;
-        let window_start = input
-            .windows(SIZE)
-            .enumerate()
-            .find(|(_, chars)| all_unique(chars))
-            .unwrap()
-            .0;
-        window_start + SIZE
+        self.part1_with(input, &Params::default())
     }
 
     fn part2(&self, input: &Self::Parsed) -> Self::Part2Output {
-        const SIZE: usize = 14;
-        let window_start = input
-            .windows(SIZE)
-            .enumerate()
-            .find(|(_, chars)| all_unique(chars))
-            .unwrap()
-            .0;
-        window_start + SIZE
+        self.part2_with(input, &Params::default())
+    }
+
+    fn part1_with(&self, input: &Self::Parsed, params: &Params) -> Self::Part1Output {
+        Marker(find_marker_bitmask(input, params.get(&PACKET_MARKER) as usize))
+    }
+
+    fn part2_with(&self, input: &Self::Parsed, params: &Params) -> Self::Part2Output {
+        Marker(find_marker_bitmask(input, params.get(&MESSAGE_MARKER) as usize))
+    }
+}
+
+/// Characters received up to the end of the first marker, or `no marker` if
+/// the signal has none.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Marker(Option<usize>);
+
+impl Display for Marker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(end) => write!(f, "{}", end),
+            None => write!(f, "no marker"),
+        }
     }
 }
 
+fn find_marker(input: &[char], size: usize) -> Option<usize> {
+    let window_start = input
+        .windows(size)
+        .position(all_unique)?;
+    Some(window_start + size)
+}
+
 fn all_unique(chars: &[char]) -> bool {
     let size = chars.len();
     for i in 0..size {
@@ -45,3 +133,60 @@ fn all_unique(chars: &[char]) -> bool {
     }
     true
 }
+
+/// A character seen twice in the window toggles its bit back off, so the
+/// window is all distinct exactly when `size` bits are set. Each ASCII
+/// character has its own bit, which `parse` ensures.
+fn find_marker_bitmask(input: &[char], size: usize) -> Option<usize> {
+    let bit = |c: char| 1u128 << c as u32;
+    let mut mask = 0;
+    for (i, &c) in input.iter().enumerate() {
+        mask ^= bit(c);
+        if i >= size {
+            mask ^= bit(input[i - size]);
+        }
+        if mask.count_ones() as usize == size {
+            return Some(i + 1);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn implementations_agree_on_mixed_case() {
+        // Characters 32 apart, like `a` and `A`, are still different.
+        let input = Solution.parse(String::from("aAaA@`@`aAbBcCdD@`eE\n")).unwrap();
+        for size in 1..=12 {
+            assert_eq!(
+                find_marker_bitmask(&input, size),
+                find_marker(&input, size),
+                "{}",
+                size
+            );
+        }
+        assert_eq!(find_marker_bitmask(&input, 12), Some(20));
+    }
+
+    #[test]
+    fn no_marker() {
+        let raw = std::fs::read_to_string("samples/2022/day06-1").unwrap();
+        let input = Solution.parse(raw).unwrap();
+        let params = Params::default().with("message_marker", 100);
+        assert_eq!(Solution.part2_with(&input, &params).to_string(), "no marker");
+        assert_eq!(Bitmask.part2_with(&input, &params).to_string(), "no marker");
+        // Only 26 letters, so never more than 26 distinct.
+        let params = params.with("message_marker", 27);
+        assert_eq!(Solution.part2_with(&input, &params), Marker(None));
+        assert_eq!(Bitmask.part2_with(&input, &params), Marker(None));
+    }
+
+    #[test]
+    fn rejects_non_ascii() {
+        let err = Bitmask.parse(String::from("abcé")).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 4));
+    }
+}
@@ -1,27 +1,64 @@
 use crate::harness::Harness;
-use std::str::Lines;
+use crate::params::{Param, Params};
+use crate::parse::{lines, Lines, ParseError};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
 
 pub struct Solution;
 
+const SIZE_LIMIT: Param = Param {
+    name: "size_limit",
+    default: 100_000,
+    min: 0,
+    max: u32::MAX as i64,
+    help: "largest directory size summed in part 1",
+};
+const TOTAL_SPACE: Param = Param {
+    name: "total_space",
+    default: 70_000_000,
+    min: 0,
+    max: u32::MAX as i64,
+    help: "size of the disk",
+};
+const SPACE_NEEDED: Param = Param {
+    name: "space_needed",
+    default: 30_000_000,
+    min: 0,
+    max: u32::MAX as i64,
+    help: "free space the update needs",
+};
+
 impl Harness for Solution {
     type Parsed = Directory;
     type Part1Output = u32;
     type Part2Output = u32;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
+    const PARAMS: &'static [Param] = &[SIZE_LIMIT, TOTAL_SPACE, SPACE_NEEDED];
+
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
         let mut root = Directory { contents: vec![] };
-        let mut lines = raw_input.lines();
+        let mut lines = lines(&raw_input);
         lines.next(); // Skip "$ cd /"
-        parse(&mut lines, &mut root);
-        root
+        parse(&mut lines, &mut root)?;
+        Ok(root)
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
+        self.part1_with(input, &Params::default())
+    }
+
+    fn part2(&self, input: &Self::Parsed) -> Self::Part2Output {
+        self.part2_with(input, &Params::default())
+    }
+
+    fn part1_with(&self, input: &Self::Parsed, params: &Params) -> Self::Part1Output {
+        let size_limit = params.get(&SIZE_LIMIT) as u32;
         let mut sum = 0;
         let mut to_visit = vec![input];
         while let Some(dir) = to_visit.pop() {
             let size = dir.recursive_size();
-            if size <= 100_000 {
+            if size <= size_limit {
                 sum += size;
             }
             for entry in dir.contents.iter() {
@@ -33,17 +70,18 @@ impl Harness for Solution {
         sum
     }
 
-    fn part2(&self, input: &Self::Parsed) -> Self::Part2Output {
-        const TOTAL_SPACE: u32 = 70_000_000;
-        const SPACE_NEEDED: u32 = 30_000_000;
+    fn part2_with(&self, input: &Self::Parsed, params: &Params) -> Self::Part2Output {
+        let total_space = params.get(&TOTAL_SPACE) as u64;
+        let space_needed = params.get(&SPACE_NEEDED) as u64;
 
-        let to_free = input.recursive_size() + SPACE_NEEDED - TOTAL_SPACE;
+        // Nothing to free if the disk already has the space.
+        let to_free = (input.recursive_size() as u64 + space_needed).saturating_sub(total_space);
         let mut smallest = u32::MAX;
 
         let mut to_visit = vec![input];
         while let Some(dir) = to_visit.pop() {
             let size = dir.recursive_size();
-            if size < smallest && size >= to_free {
+            if size < smallest && size as u64 >= to_free {
                 smallest = size;
             }
             for entry in dir.contents.iter() {
@@ -55,28 +93,106 @@ impl Harness for Solution {
 
         smallest
     }
+
+    /// `size` directories, each usually made in the one made before it so
+    /// the tree gets deep, holding between 40 and 70 million bytes in all
+    /// so that part 2 has something to free.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        let mut children = vec![Vec::new()];
+        for dir in 1..size.max(1) {
+            let parent = if rng.gen_bool(0.75) {
+                dir - 1
+            } else {
+                rng.gen_range(0..dir)
+            };
+            children[parent].push(dir);
+            children.push(Vec::new());
+        }
+        let names: Vec<String> = children.iter().map(|_| name(rng)).collect();
+
+        let file_counts: Vec<usize> = (0..children.len())
+            .map(|dir| rng.gen_range(usize::from(dir == 0)..=4))
+            .collect();
+        let target: u32 = rng.gen_range(40_000_001..=70_000_000);
+        let largest = (2 * target as usize / file_counts.iter().sum::<usize>()).max(1) as u32;
+        let mut sizes: Vec<Vec<u32>> = file_counts
+            .iter()
+            .map(|&n| (0..n).map(|_| rng.gen_range(1..=largest)).collect())
+            .collect();
+        // The root always has a file to make up any shortfall.
+        let total: u32 = sizes.iter().flatten().sum();
+        if total < target {
+            sizes[0][0] += target - total;
+        }
+
+        let mut out = String::from("$ cd /\n");
+        let mut list = |out: &mut String, dir: usize| {
+            let mut entries: Vec<String> = children[dir]
+                .iter()
+                .map(|&child| format!("dir {}\n", names[child]))
+                .chain(sizes[dir].iter().map(|size| format!("{} {}\n", size, file_name(rng))))
+                .collect();
+            entries.shuffle(rng);
+            out.push_str("$ ls\n");
+            out.extend(entries);
+        };
+        // Walks the tree without recursing, as it can be deep.
+        list(&mut out, 0);
+        let mut path = vec![(0, 0)];
+        while let Some(&(dir, next)) = path.last() {
+            match children[dir].get(next) {
+                Some(&child) => {
+                    path.last_mut().unwrap().1 += 1;
+                    out += &format!("$ cd {}\n", names[child]);
+                    list(&mut out, child);
+                    path.push((child, 0));
+                }
+                None => {
+                    path.pop();
+                    if !path.is_empty() {
+                        out += "$ cd ..\n";
+                    }
+                }
+            }
+        }
+        Some(out)
+    }
+}
+
+fn name(rng: &mut StdRng) -> String {
+    (0..rng.gen_range(1..=8))
+        .map(|_| rng.gen_range('a'..='z'))
+        .collect()
+}
+
+fn file_name(rng: &mut StdRng) -> String {
+    match rng.gen_range(0..3) {
+        0 => name(rng),
+        _ => format!("{}.{}", name(rng), name(rng)),
+    }
 }
 
-fn parse(lines: &mut Lines<'_>, cwd: &mut Directory) {
+fn parse(lines: &mut Lines<'_>, cwd: &mut Directory) -> Result<(), ParseError> {
     while let Some(line) = lines.next() {
-        match line.split_once(' ').unwrap() {
+        match line.split_once(" ")? {
             ("$", "ls") => {}
-            ("$", command) => match command.split_once(' ').unwrap() {
-                ("cd", "..") => return,
-                ("cd", _name) => {
+            ("$", command) => match command.split_once(' ') {
+                Some(("cd", "..")) => return Ok(()),
+                Some(("cd", _name)) => {
                     let mut subdir = Directory { contents: vec![] };
-                    parse(lines, &mut subdir);
+                    parse(lines, &mut subdir)?;
                     cwd.contents.push(Entry::Directory(subdir));
                 }
-                _ => unreachable!(),
+                _ => return Err(line.error(command, "unknown command")),
             },
             ("dir", _name) => {}
             (size, _name) => {
-                let size: u32 = size.parse().unwrap();
+                let size: u32 = line.parse(size)?;
                 cwd.contents.push(Entry::File(File { size }))
             }
         }
     }
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -116,7 +232,7 @@ mod tests {
     fn part1_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day07-1").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         assert_eq!(s.part1(&input), 95437);
     }
 
@@ -124,7 +240,7 @@ mod tests {
     fn part2_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day07-1").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         assert_eq!(s.part2(&input), 24933642);
     }
 }
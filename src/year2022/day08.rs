@@ -1,4 +1,7 @@
 use crate::harness::Harness;
+use crate::parse::{lines, ParseError};
+use rand::rngs::StdRng;
+use rand::Rng;
 use rayon::iter::IndexedParallelIterator;
 use rayon::iter::IntoParallelRefIterator;
 use rayon::iter::ParallelIterator;
@@ -10,14 +13,20 @@ impl Harness for Solution {
     type Part1Output = usize;
     type Part2Output = u32;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
         let mut width = 0;
         let mut height = 0;
         let mut combined = Vec::new();
-        for (i, line) in raw_input.lines().enumerate() {
-            width = line.len();
+        for (i, line) in lines(&raw_input).enumerate() {
+            if i > 0 && line.text.len() != width {
+                return Err(line.error_at(0, format!("expected {} trees", width)));
+            }
+            width = line.text.len();
             height = i + 1;
-            for (j, c) in line.bytes().enumerate() {
+            for (j, c) in line.text.bytes().enumerate() {
+                if !c.is_ascii_digit() {
+                    return Err(line.error_at(j, "expected a digit"));
+                }
                 combined.push(Tree {
                     height: c - b'0',
                     visible: i == 0 || j == 0 || j == width - 1,
@@ -29,7 +38,7 @@ impl Harness for Solution {
             tree.visible = true;
         }
 
-        (width, combined)
+        Ok((width, combined))
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
@@ -130,6 +139,17 @@ impl Harness for Solution {
             .max()
             .unwrap_or_default()
     }
+
+    /// A `size` by `size` forest.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        let size = size.max(1);
+        let mut out = String::with_capacity(size * (size + 1));
+        for _ in 0..size {
+            out.extend((0..size).map(|_| rng.gen_range('0'..='9')));
+            out.push('\n');
+        }
+        Some(out)
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -150,7 +170,7 @@ mod tests {
     fn part1_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day08-1").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         assert_eq!(s.part1(&input), 21);
     }
 
@@ -158,7 +178,7 @@ mod tests {
     fn part2_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day08-1").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         assert_eq!(s.part2(&input), 8);
     }
 }
@@ -1,27 +1,41 @@
 use crate::harness::Harness;
+use crate::params::{Param, Params};
+use crate::parse::{lines, ParseError};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
 use std::collections::HashSet;
 
 pub struct Solution;
 
+const LENGTH: Param = Param {
+    name: "length",
+    default: 10,
+    min: 1,
+    max: 10_000,
+    help: "knots in the long rope of part 2",
+};
+
 impl Harness for Solution {
     type Parsed = Vec<Movement>;
     type Part1Output = usize;
     type Part2Output = usize;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        raw_input
-            .lines()
+    const PARAMS: &'static [Param] = &[LENGTH];
+
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        lines(&raw_input)
             .map(|l| {
-                let (d, n) = l.split_once(' ').unwrap();
+                let (d, n) = l.split_once(" ")?;
                 let direction = match d {
                     "U" => Direction::Up,
                     "D" => Direction::Down,
                     "L" => Direction::Left,
                     "R" => Direction::Right,
-                    _ => unreachable!(),
+                    _ => return Err(l.error(d, "expected one of `U`, `D`, `L`, `R`")),
                 };
-                let number = n.parse().unwrap();
-                Movement { direction, number }
+                let number = l.parse(n)?;
+                Ok(Movement { direction, number })
             })
             .collect()
     }
@@ -45,25 +59,38 @@ impl Harness for Solution {
     }
 
     fn part2(&self, input: &Self::Parsed) -> Self::Part2Output {
-        const LENGTH: usize = 10;
-        let mut chain = [Position(0, 0); LENGTH];
+        self.part2_with(input, &Params::default())
+    }
+
+    fn part2_with(&self, input: &Self::Parsed, params: &Params) -> Self::Part2Output {
+        let length = params.get(&LENGTH) as usize;
+        let mut chain = vec![Position(0, 0); length];
 
         let mut visited = HashSet::new();
-        visited.insert(chain[LENGTH - 1]);
+        visited.insert(chain[length - 1]);
 
         for movement in input.iter() {
             for _ in 0..movement.number {
                 chain[0].apply(movement.direction);
-                for i in 0..(LENGTH - 1) {
+                for i in 0..(length - 1) {
                     let leader = chain[i];
                     chain[i + 1].snap_to(&leader);
                 }
-                visited.insert(chain[LENGTH - 1]);
+                visited.insert(chain[length - 1]);
             }
         }
 
         visited.len()
     }
+
+    /// `size` motions.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        let motions = (0..size).map(|_| {
+            let direction = ["U", "D", "L", "R"].choose(rng).unwrap();
+            format!("{} {}\n", direction, rng.gen_range(1..=19))
+        });
+        Some(motions.collect())
+    }
 }
 
 #[derive(Hash, Eq, PartialEq, Copy, Clone)]
@@ -119,15 +146,24 @@ mod tests {
     fn part1_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day09-1").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         assert_eq!(s.part1(&input), 13);
     }
 
+    #[test]
+    fn part2_sample1_two_knots() {
+        let s = Solution {};
+        let raw = read_to_string("samples/2022/day09-1").unwrap();
+        let input = s.parse(raw).unwrap();
+        let params = Params::default().with("length", 2);
+        assert_eq!(s.part2_with(&input, &params), s.part1(&input));
+    }
+
     #[test]
     fn part2_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day09-1").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         assert_eq!(s.part2(&input), 1);
     }
 
@@ -135,7 +171,7 @@ mod tests {
     fn part2_sample2() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day09-2").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         assert_eq!(s.part2(&input), 36);
     }
 }
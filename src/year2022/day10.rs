@@ -1,18 +1,18 @@
+use crate::common::ocr::Screen;
 use crate::harness::Harness;
-use std::str::FromStr;
+use crate::parse::{lines, Line, ParseError};
+use rand::rngs::StdRng;
+use rand::Rng;
 
 pub struct Solution;
 
 impl Harness for Solution {
     type Parsed = Vec<Instruction>;
     type Part1Output = i32;
-    type Part2Output = String;
+    type Part2Output = Screen;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        raw_input
-            .lines()
-            .map(|line| line.parse().unwrap())
-            .collect()
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        lines(&raw_input).map(|l| Instruction::parse(&l)).collect()
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
@@ -77,7 +77,26 @@ impl Harness for Solution {
             }
         }
 
-        rendered
+        Screen { render: rendered }
+    }
+
+    /// `size` instructions, keeping the sprite around the screen.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        let mut reg_x = 1;
+        let mut out = String::new();
+        for _ in 0..size.max(1) {
+            if rng.gen_bool(1.0 / 3.0) {
+                out += "noop\n";
+                continue;
+            }
+            let mut v = rng.gen_range(1..=10) * if rng.gen() { 1 } else { -1 };
+            if !(-1..=40).contains(&(reg_x + v)) {
+                v = -v;
+            }
+            reg_x += v;
+            out += &format!("addx {}\n", v);
+        }
+        Some(out)
     }
 }
 
@@ -87,18 +106,16 @@ pub enum Instruction {
     AddX(i32),
 }
 
-impl FromStr for Instruction {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(' ');
-        match parts.next().unwrap() {
+impl Instruction {
+    fn parse(line: &Line) -> Result<Self, ParseError> {
+        let mut parts = line.text.split(' ');
+        match line.expect(&mut parts, "an instruction")? {
             "noop" => Ok(Self::Noop),
             "addx" => {
-                let payload = parts.next().unwrap().parse().unwrap();
+                let payload = line.parse(line.expect(&mut parts, "an operand")?)?;
                 Ok(Self::AddX(payload))
             }
-            _ => Err(()),
+            other => Err(line.error(other, "unknown instruction")),
         }
     }
 }
@@ -112,7 +129,7 @@ mod tests {
     fn part1_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day10-1").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         assert_eq!(s.part1(&input), 13140);
     }
 
@@ -120,7 +137,7 @@ mod tests {
     fn part2_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day10-1").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         let expected = String::from(
             "
 ##..##..##..##..##..##..##..##..##..##..
@@ -130,6 +147,8 @@ mod tests {
 ######......######......######......####
 #######.......#######.......#######.....",
         );
-        assert_eq!(s.part2(&input), expected);
+        // The sample draws no letters, so the render is the answer.
+        assert_eq!(s.part2(&input).render, expected);
+        assert_eq!(s.part2(&input).to_string(), expected);
     }
 }
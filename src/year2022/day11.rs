@@ -1,88 +1,222 @@
 use crate::harness::Harness;
-use std::str::Lines;
+use crate::params::{Param, Params};
+use crate::parse::{lines, Line, Lines, ParseError};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt::{self, Display, Write};
 
 pub struct Solution;
 
+const ROUNDS: Param = Param {
+    name: "rounds",
+    default: 20,
+    min: 0,
+    max: 1_000_000,
+    help: "rounds played in part 1",
+};
+const RELIEF: Param = Param {
+    name: "relief",
+    default: 3,
+    min: 1,
+    max: 1_000_000,
+    help: "divisor of worry levels after each inspection in part 1",
+};
+const ROUNDS_WITHOUT_RELIEF: Param = Param {
+    name: "rounds_without_relief",
+    default: 10_000,
+    min: 0,
+    max: 1_000_000,
+    help: "rounds played in part 2",
+};
+
 impl Harness for Solution {
     type Parsed = (Vec<Monkey>, Vec<Vec<i64>>);
-    type Part1Output = usize;
-    type Part2Output = usize;
+    type Part1Output = MonkeyBusiness;
+    type Part2Output = MonkeyBusiness;
+
+    const PARAMS: &'static [Param] = &[ROUNDS, RELIEF, ROUNDS_WITHOUT_RELIEF];
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        let mut lines = raw_input.lines();
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        let mut lines = lines(&raw_input);
         let mut monkeys = Vec::new();
         let mut all_items = Vec::new();
 
-        while let Some((monkey, items)) = Monkey::parse(&mut lines) {
+        let mut target_positions = Vec::new();
+
+        while let Some((monkey, items, positions)) = Monkey::parse(&mut lines)? {
             monkeys.push(monkey);
             all_items.push(items);
-            lines.next();
+            target_positions.push(positions);
         }
 
-        (monkeys, all_items)
-    }
-
-    fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
-        let monkeys = &input.0;
-        let mut all_items = input.1.clone();
-        let mut inspections = vec![0; monkeys.len()];
-
-        const ROUNDS: usize = 20;
-        for _ in 0..ROUNDS {
-            for (i, monkey) in monkeys.iter().enumerate() {
-                let items: Vec<_> = all_items[i].drain(..).collect();
-                inspections[i] += items.len();
-
-                for item in items.into_iter() {
-                    let new = monkey.operation.apply(item) / 3;
-
-                    let target = if new % monkey.test_divisor == 0 {
-                        monkey.true_target
-                    } else {
-                        monkey.false_target
-                    };
-
-                    all_items[target].push(new);
+        for (i, (monkey, positions)) in monkeys.iter().zip(&target_positions).enumerate() {
+            let targets = [monkey.true_target, monkey.false_target];
+            for (target, &(line, column)) in targets.into_iter().zip(positions) {
+                if target >= monkeys.len() {
+                    let message = format!("no monkey {}", target);
+                    return Err(ParseError::new(line, column, message));
+                }
+                if target == i {
+                    let message = "a monkey cannot throw to itself";
+                    return Err(ParseError::new(line, column, message));
                 }
             }
         }
 
-        inspections.sort_unstable();
-        inspections.into_iter().rev().take(2).product()
+        Ok((monkeys, all_items))
+    }
+
+    fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
+        self.part1_with(input, &Params::default())
     }
 
     fn part2(&self, input: &Self::Parsed) -> Self::Part2Output {
-        let monkeys = &input.0;
-        let mut all_items = input.1.clone();
-        let mut inspections = vec![0; monkeys.len()];
+        self.part2_with(input, &Params::default())
+    }
+
+    fn part1_with(&self, input: &Self::Parsed, params: &Params) -> Self::Part1Output {
+        let (monkeys, items) = input;
+        let (rounds, relief) = (params.get(&ROUNDS), params.get(&RELIEF));
+        MonkeyBusiness(monkey_business(monkeys, items, rounds, |worry| worry / relief))
+    }
+
+    fn part2_with(&self, input: &Self::Parsed, params: &Params) -> Self::Part2Output {
+        let (monkeys, items) = input;
+        let rounds = params.get(&ROUNDS_WITHOUT_RELIEF);
+        let combined_divisor = monkeys
+            .iter()
+            .try_fold(1i64, |product, m| product.checked_mul(m.test_divisor));
+        MonkeyBusiness(combined_divisor.and_then(|combined_divisor| {
+            monkey_business(monkeys, items, rounds, |worry| worry % combined_divisor)
+        }))
+    }
 
-        let combined_divisor: i64 = monkeys.iter().map(|m| m.test_divisor).product();
+    /// Eight monkeys sharing `size` items, drawn again until part 1's
+    /// worry levels fit in an `i64`; the monkey squaring them can make them
+    /// outgrow it.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        const MONKEYS: usize = 8;
+        loop {
+            let mut divisors = vec![2, 3, 5, 7, 11, 13, 17, 19, 23];
+            divisors.shuffle(rng);
+            let mut operations: Vec<_> = (0..MONKEYS)
+                .map(|i| match i {
+                    0 => Operation::Relative(Operator::Multiply),
+                    1 => Operation::Constant(Operator::Multiply, rng.gen_range(2..=19)),
+                    _ => Operation::Constant(Operator::Add, rng.gen_range(1..=8)),
+                })
+                .collect();
+            operations.shuffle(rng);
+            let monkeys: Vec<_> = operations
+                .into_iter()
+                .zip(divisors)
+                .enumerate()
+                .map(|(i, (operation, test_divisor))| {
+                    let mut targets: Vec<_> = (0..MONKEYS).filter(|&t| t != i).collect();
+                    targets.shuffle(rng);
+                    Monkey {
+                        operation,
+                        test_divisor,
+                        true_target: targets[0],
+                        false_target: targets[1],
+                    }
+                })
+                .collect();
+            let mut items = vec![Vec::new(); MONKEYS];
+            for i in 0..size.max(MONKEYS) {
+                let monkey = if i < MONKEYS {
+                    i
+                } else {
+                    rng.gen_range(0..MONKEYS)
+                };
+                items[monkey].push(rng.gen_range(50..=99));
+            }
 
-        const ROUNDS: usize = 10_000;
-        for _ in 0..ROUNDS {
-            for (i, monkey) in monkeys.iter().enumerate() {
-                let items: Vec<_> = all_items[i].drain(..).collect();
-                inspections[i] += items.len();
+            let relieve = |worry| worry / RELIEF.default;
+            if monkey_business(&monkeys, &items, ROUNDS.default, relieve).is_some() {
+                return Some(render(&monkeys, &items));
+            }
+        }
+    }
+}
 
-                for item in items.into_iter() {
-                    let new = monkey.operation.apply(item) % combined_divisor;
+/// The product of the two most inspections after `rounds`, with `relieve`
+/// applied to each worry level after its operation. `None` if a worry level
+/// overflows.
+fn monkey_business(
+    monkeys: &[Monkey],
+    items: &[Vec<i64>],
+    rounds: i64,
+    relieve: impl Fn(i64) -> i64,
+) -> Option<usize> {
+    let mut all_items = items.to_vec();
+    let mut inspections = vec![0; monkeys.len()];
 
-                    let target = if new % monkey.test_divisor == 0 {
-                        monkey.true_target
-                    } else {
-                        monkey.false_target
-                    };
+    for _ in 0..rounds {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            let items = std::mem::take(&mut all_items[i]);
+            inspections[i] += items.len();
 
-                    all_items[target].push(new);
-                }
+            for item in items {
+                let new = relieve(monkey.operation.checked_apply(item)?);
+
+                let target = if new % monkey.test_divisor == 0 {
+                    monkey.true_target
+                } else {
+                    monkey.false_target
+                };
+
+                all_items[target].push(new);
             }
         }
+    }
 
-        inspections.sort_unstable();
-        inspections.into_iter().rev().take(2).product()
+    inspections.sort_unstable();
+    Some(inspections.into_iter().rev().take(2).product())
+}
+
+/// The level of monkey business, or `worry overflow` if a worry level
+/// outgrew an `i64` on the way.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MonkeyBusiness(Option<usize>);
+
+impl Display for MonkeyBusiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(level) => write!(f, "{}", level),
+            None => write!(f, "worry overflow"),
+        }
     }
 }
 
+fn render(monkeys: &[Monkey], items: &[Vec<i64>]) -> String {
+    let mut out = String::new();
+    for (i, (monkey, items)) in monkeys.iter().zip(items).enumerate() {
+        let items: Vec<_> = items.iter().map(i64::to_string).collect();
+        let operation = match monkey.operation {
+            Operation::Constant(Operator::Add, n) => format!("old + {}", n),
+            Operation::Constant(Operator::Multiply, n) => format!("old * {}", n),
+            Operation::Relative(Operator::Add) => String::from("old + old"),
+            Operation::Relative(Operator::Multiply) => String::from("old * old"),
+        };
+        let _ = writeln!(
+            out,
+            "Monkey {}:\n  Starting items: {}\n  Operation: new = {}\n  \
+             Test: divisible by {}\n    If true: throw to monkey {}\n    \
+             If false: throw to monkey {}\n",
+            i,
+            items.join(", "),
+            operation,
+            monkey.test_divisor,
+            monkey.true_target,
+            monkey.false_target
+        );
+    }
+    out
+}
+
+#[derive(Debug)]
 pub struct Monkey {
     operation: Operation,
     test_divisor: i64,
@@ -90,39 +224,57 @@ pub struct Monkey {
     false_target: usize,
 }
 
+/// Line and column of a monkey's true and false targets, checked once every
+/// monkey has been read.
+type TargetPositions = [(usize, usize); 2];
+
 impl Monkey {
-    fn parse(lines: &mut Lines<'_>) -> Option<(Self, Vec<i64>)> {
+    fn parse(
+        lines: &mut Lines<'_>,
+    ) -> Result<Option<(Self, Vec<i64>, TargetPositions)>, ParseError> {
         // Monkey #
-        lines.next()?;
+        let Some(header) = lines.find(|l| !l.text.is_empty()) else {
+            return Ok(None);
+        };
+        if !header.text.starts_with("Monkey") {
+            return Err(header.error_at(0, "expected `Monkey`"));
+        }
 
         // Starting items
-        let items: Vec<i64> = lines
-            .next()?
+        let line = lines.expect("starting items")?;
+        let items = line
             .split_once(": ")?
             .1
             .split(", ")
-            .map(|s| s.parse().unwrap())
-            .collect();
+            .map(|s| line.parse(s))
+            .collect::<Result<Vec<i64>, _>>()?;
 
         // Operation
-        let mut op = lines.next()?.split_once("= ")?.1.split(' ');
-        op.next()?; // "old"
-        let operation = match (op.next()?, op.next()?) {
+        let line = lines.expect("an operation")?;
+        let mut op = line.split_once("= ")?.1.split(' ');
+        line.expect(&mut op, "`old`")?; // "old"
+        let operator = line.expect(&mut op, "an operator")?;
+        let operation = match (operator, line.expect(&mut op, "an operand")?) {
             ("+", "old") => Operation::Relative(Operator::Add),
-            ("+", n) => Operation::Constant(Operator::Add, n.parse().ok()?),
+            ("+", n) => Operation::Constant(Operator::Add, line.parse(n)?),
             ("*", "old") => Operation::Relative(Operator::Multiply),
-            ("*", n) => Operation::Constant(Operator::Multiply, n.parse().ok()?),
-            _ => unreachable!(),
+            ("*", n) => Operation::Constant(Operator::Multiply, line.parse(n)?),
+            _ => return Err(line.error(operator, "expected `+` or `*`")),
         };
 
         // Test divisor
-        let test_divisor: i64 = lines.next()?.split_once(" by ")?.1.parse().ok()?;
+        let line = lines.expect("a test")?;
+        let divisor = line.split_once(" by ")?.1;
+        let test_divisor: i64 = line.parse(divisor)?;
+        if test_divisor <= 0 {
+            return Err(line.error(divisor, "expected a positive divisor"));
+        }
 
         // Targets
-        let true_target: usize = lines.next()?.rsplit(' ').next()?.parse().ok()?;
-        let false_target: usize = lines.next()?.rsplit(' ').next()?.parse().ok()?;
+        let (true_target, true_position) = Self::parse_target(&lines.expect("a target")?)?;
+        let (false_target, false_position) = Self::parse_target(&lines.expect("a target")?)?;
 
-        Some((
+        Ok(Some((
             Monkey {
                 operation,
                 test_divisor,
@@ -130,26 +282,36 @@ impl Monkey {
                 false_target,
             },
             items,
-        ))
+            [true_position, false_position],
+        )))
+    }
+
+    /// The monkey thrown to, with its line and column.
+    fn parse_target(line: &Line) -> Result<(usize, (usize, usize)), ParseError> {
+        let target = line.expect(&mut line.text.rsplit(' '), "a target")?;
+        let column = line.text.len() - target.len() + 1;
+        Ok((line.parse(target)?, (line.number, column)))
     }
 }
 
+#[derive(Debug)]
 pub enum Operation {
     Constant(Operator, i64),
     Relative(Operator),
 }
 
 impl Operation {
-    fn apply(&self, old: i64) -> i64 {
+    fn checked_apply(&self, old: i64) -> Option<i64> {
         match self {
-            Self::Constant(Operator::Add, n) => old + n,
-            Self::Constant(Operator::Multiply, n) => old * n,
-            Self::Relative(Operator::Add) => old + old,
-            Self::Relative(Operator::Multiply) => old * old,
+            Self::Constant(Operator::Add, n) => old.checked_add(*n),
+            Self::Constant(Operator::Multiply, n) => old.checked_mul(*n),
+            Self::Relative(Operator::Add) => old.checked_add(old),
+            Self::Relative(Operator::Multiply) => old.checked_mul(old),
         }
     }
 }
 
+#[derive(Debug)]
 pub enum Operator {
     Add,
     Multiply,
@@ -164,15 +326,52 @@ mod tests {
     fn part1_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day11-1").unwrap();
-        let input = s.parse(raw);
-        assert_eq!(s.part1(&input), 10605);
+        let input = s.parse(raw).unwrap();
+        assert_eq!(s.part1(&input), MonkeyBusiness(Some(10605)));
     }
 
     #[test]
     fn part2_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day11-1").unwrap();
-        let input = s.parse(raw);
-        assert_eq!(s.part2(&input), 2713310158);
+        let input = s.parse(raw).unwrap();
+        assert_eq!(s.part2(&input), MonkeyBusiness(Some(2713310158)));
+    }
+
+    #[test]
+    fn part2_sample1_20_rounds() {
+        let s = Solution {};
+        let raw = read_to_string("samples/2022/day11-1").unwrap();
+        let input = s.parse(raw).unwrap();
+        let params = Params::default().with("rounds_without_relief", 20);
+        assert_eq!(s.part2_with(&input, &params), MonkeyBusiness(Some(99 * 103)));
+    }
+
+    #[test]
+    fn malformed_monkeys() {
+        let s = Solution {};
+        let raw = read_to_string("samples/2022/day11-1").unwrap();
+
+        let err = s.parse(raw.replacen("monkey 2", "monkey 9", 1)).unwrap_err();
+        assert_eq!((err.line, err.column, err.message.as_str()), (5, 30, "no monkey 9"));
+        let err = s.parse(raw.replacen("monkey 2", "monkey 0", 1)).unwrap_err();
+        assert_eq!(err.message, "a monkey cannot throw to itself");
+        let err = s.parse(raw.replacen("by 23", "by 0", 1)).unwrap_err();
+        assert_eq!((err.line, err.column), (4, 22));
+
+        // Monkeys need no blank line between them.
+        let input = s.parse(raw.replace("\n\n", "\n")).unwrap();
+        assert_eq!(s.part1(&input), MonkeyBusiness(Some(10605)));
+    }
+
+    #[test]
+    fn worry_overflow() {
+        let s = Solution {};
+        let raw = read_to_string("samples/2022/day11-1").unwrap();
+        let input = s.parse(raw).unwrap();
+        let params = Params::default().with("relief", 1);
+        assert_eq!(s.part1_with(&input, &params).to_string(), "worry overflow");
+        let params = Params::default().with("rounds", 200);
+        assert_eq!(s.part1_with(&input, &params).to_string(), "worry overflow");
     }
 }
@@ -1,104 +1,223 @@
-use crate::common::dijkstra::{shortest_path, shortest_path_matching, Edge};
+use crate::common::bfs::bfs_matching;
+use crate::common::dijkstra::Graph;
 use crate::harness::Harness;
+use crate::parse::{lines, ParseError};
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
 
 pub struct Solution;
 
 impl Harness for Solution {
     type Parsed = Input;
-    type Part1Output = usize;
-    type Part2Output = usize;
-
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        let mut start = Position { row: 0, col: 0 };
-        let mut end = Position { row: 0, col: 0 };
-        let map = raw_input
-            .lines()
+    type Part1Output = Route;
+    type Part2Output = Route;
+
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        let mut start = None;
+        let mut end = None;
+        let mut width = None;
+        let map = lines(&raw_input)
             .enumerate()
             .map(|(r, l)| {
-                l.bytes()
+                let row: Vec<u8> = l
+                    .text
+                    .bytes()
                     .enumerate()
                     .map(|(c, b)| match b {
                         b'S' => {
-                            start = Position { row: r, col: c };
-                            b'a'
+                            start = Some(Position { row: r, col: c });
+                            Ok(b'a')
                         }
                         b'E' => {
-                            end = Position { row: r, col: c };
-                            b'z'
+                            end = Some(Position { row: r, col: c });
+                            Ok(b'z')
                         }
-                        c => c,
+                        b'a'..=b'z' => Ok(b),
+                        _ => Err(l.error_at(c, "expected a height")),
                     })
-                    .collect()
+                    .collect::<Result<_, _>>()?;
+                let width = *width.get_or_insert(row.len());
+                if row.len() != width {
+                    let message = format!("expected a row of {} heights like the first", width);
+                    return Err(l.error_at(row.len().min(width), message));
+                }
+                Ok(row)
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
-        Input { start, end, map }
+        let missing = |what| ParseError::new(1, 1, format!("no {} position marked", what));
+        Ok(Input {
+            start: start.ok_or_else(|| missing("start"))?,
+            end: end.ok_or_else(|| missing("end"))?,
+            map,
+        })
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
-        let edges = make_adjacency_list(&input.map, |curr, next| next <= curr + 1);
-        let start = input.start.row * input.map[0].len() + input.start.col;
-        let end = input.end.row * input.map[0].len() + input.end.col;
-        shortest_path(&edges, start, end).unwrap()
+        climb(input, [input.start])
     }
 
     fn part2(&self, input: &Self::Parsed) -> Self::Part2Output {
-        let edges = make_adjacency_list(&input.map, |curr, next| next >= curr - 1);
-        let width = input.map[0].len();
-        let start = input.end.row * width + input.end.col;
-        let is_goal = |index: usize| {
-            let r = index / width;
-            let c = index % width;
-            input.map[r][c] == b'a'
-        };
-        shortest_path_matching(&edges, start, is_goal).unwrap()
+        let lowest = input.map.iter().enumerate().flat_map(|(row, heights)| {
+            heights
+                .iter()
+                .enumerate()
+                .filter(|(_, &h)| h == b'a')
+                .map(move |(col, _)| Position { row, col })
+        });
+        climb(input, lowest)
     }
-}
-
-fn make_adjacency_list<F>(map: &Vec<Vec<u8>>, can_transit: F) -> Vec<Vec<Edge>>
-where
-    F: Fn(u8, u8) -> bool,
-{
-    let height = map.len();
-    let width = map[0].len();
 
-    let mut all_edges = Vec::new();
-    let make_index = |row: usize, col: usize| row * width + col;
+    /// A map `size` wide over gently sloping terrain, with a climbable path
+    /// from `S` on the left edge to `E` on the right one.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        let width = size.max(26);
+        let height = (size / 4).max(5);
+        let mut map = vec![vec![0; width]; height];
+        for row in 0..height {
+            for col in 0..width {
+                let around: Vec<i32> = [
+                    row.checked_sub(1).map(|r| map[r][col]),
+                    col.checked_sub(1).map(|c| map[row][c]),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                let base = match around.len() {
+                    0 => rng.gen_range(0..26),
+                    n => around.iter().sum::<i32>() / n as i32,
+                };
+                map[row][col] = (base + rng.gen_range(-1..=1)).clamp(0, 25);
+            }
+        }
 
-    for row in 0..height {
+        // Up or down each column to a random row, then on to the next. With
+        // at least 26 steps the heights along it rise by at most one.
+        let mut path = Vec::new();
+        let mut row = rng.gen_range(0..height);
         for col in 0..width {
-            let value = map[row][col];
-            let mut edges = Vec::new();
-
-            let mut add_neighbor = |nr: usize, nc: usize| {
-                let index = make_index(nr, nc);
-                let next_value = map[nr][nc];
-                if can_transit(value, next_value) {
-                    edges.push(Edge::new(index, 1));
-                }
+            let to = if col + 1 < width {
+                rng.gen_range(0..height)
+            } else {
+                row
             };
-
-            if row > 0 {
-                add_neighbor(row - 1, col);
+            path.push((row, col));
+            while row != to {
+                row = if to > row { row + 1 } else { row - 1 };
+                path.push((row, col));
             }
+        }
+        for (i, &(row, col)) in path.iter().enumerate() {
+            map[row][col] = (i * 26 / path.len()) as i32;
+        }
 
-            if col > 0 {
-                add_neighbor(row, col - 1);
-            }
+        let mut out: Vec<Vec<u8>> = map
+            .iter()
+            .map(|row| row.iter().map(|&h| b'a' + h as u8).collect())
+            .collect();
+        let (start, end) = (path[0], path[path.len() - 1]);
+        out[start.0][start.1] = b'S';
+        out[end.0][end.1] = b'E';
+        Some(
+            out.into_iter()
+                .map(|row| String::from_utf8(row).unwrap() + "\n")
+                .collect(),
+        )
+    }
+}
 
-            if row < height - 1 {
-                add_neighbor(row + 1, col);
-            }
+/// The shortest hike from any of `starts` up to the end.
+fn climb(input: &Input, starts: impl IntoIterator<Item = Position>) -> Route {
+    let hill = Hill { map: &input.map };
+    let neighbors = |at: &Position| hill.neighbors(*at).map(|(next, _)| next);
+    let reached = bfs_matching(starts, neighbors, |at| *at == input.end);
+    match reached.path_to(&input.end) {
+        Some(nodes) => Route::new(input, &nodes),
+        None => Route {
+            steps: None,
+            render: String::new(),
+        },
+    }
+}
 
-            if col < width - 1 {
-                add_neighbor(row, col + 1);
-            }
+/// The squares of the map, each leading to the neighbors at most one
+/// higher.
+struct Hill<'a> {
+    map: &'a [Vec<u8>],
+}
+
+impl Graph for Hill<'_> {
+    type Node = Position;
+    type Cost = usize;
+
+    fn neighbors(&self, from: Position) -> impl Iterator<Item = (Position, usize)> {
+        let Position { row, col } = from;
+        [
+            row.checked_sub(1).map(|row| Position { row, col }),
+            col.checked_sub(1).map(|col| Position { row, col }),
+            (row + 1 < self.map.len()).then_some(Position { row: row + 1, col }),
+            (col + 1 < self.map[0].len()).then_some(Position { row, col: col + 1 }),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(move |to| self.height(*to) <= self.height(from) + 1)
+        .map(|to| (to, 1))
+    }
+}
+
+impl Hill<'_> {
+    fn height(&self, at: Position) -> u8 {
+        self.map[at.row][at.col]
+    }
+}
+
+/// The fewest steps of a hike, in its alternate form also drawing the route
+/// on the map as the puzzle does, with an arrow on each square left behind.
+pub struct Route {
+    /// `None` if the end cannot be reached.
+    steps: Option<usize>,
+    render: String,
+}
 
-            all_edges.push(edges);
+impl Route {
+    /// `nodes` are the squares from the first to the last.
+    fn new(input: &Input, nodes: &[Position]) -> Self {
+        let mut squares = vec![vec![b'.'; input.map[0].len()]; input.map.len()];
+        for pair in nodes.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let arrow = match (to.row.cmp(&from.row), to.col.cmp(&from.col)) {
+                (_, Ordering::Greater) => b'>',
+                (_, Ordering::Less) => b'<',
+                (Ordering::Greater, _) => b'v',
+                _ => b'^',
+            };
+            squares[from.row][from.col] = arrow;
+        }
+        let last = nodes[nodes.len() - 1];
+        squares[last.row][last.col] = b'E';
+
+        let render = squares
+            .into_iter()
+            .map(|row| String::from_utf8(row).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self {
+            steps: Some(nodes.len() - 1),
+            render,
         }
     }
+}
 
-    all_edges
+impl Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.steps {
+            Some(steps) if f.alternate() => write!(f, "{}\n{}", steps, self.render),
+            Some(steps) => write!(f, "{}", steps),
+            None => write!(f, "no route"),
+        }
+    }
 }
 
 pub struct Input {
@@ -107,6 +226,7 @@ pub struct Input {
     map: Vec<Vec<u8>>,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 struct Position {
     row: usize,
     col: usize,
@@ -115,21 +235,66 @@ struct Position {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::dijkstra::{a_star, manhattan};
+    use rand::SeedableRng;
     use std::fs::read_to_string;
 
     #[test]
     fn part1_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day12-1").unwrap();
-        let input = s.parse(raw);
-        assert_eq!(s.part1(&input), 31);
+        let input = s.parse(raw).unwrap();
+        let route = s.part1(&input);
+        assert_eq!(route.steps, Some(31));
+        // One of the routes as short as the puzzle's.
+        assert_eq!(
+            format!("{:#}", route),
+            "31\n\
+             v..v<<<<\n\
+             >v.vv<<^\n\
+             .v.v>E^^\n\
+             .>v>>>^^\n\
+             ..>>>>>^"
+        );
     }
 
     #[test]
     fn part2_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day12-1").unwrap();
-        let input = s.parse(raw);
-        assert_eq!(s.part2(&input), 29);
+        let input = s.parse(raw).unwrap();
+        assert_eq!(s.part2(&input).to_string(), "29");
+    }
+
+    #[test]
+    fn ragged_or_unreachable() {
+        let s = Solution {};
+        let err = s.parse(String::from("Sabc\nabcE\nab\n")).err().unwrap();
+        assert_eq!((err.line, err.column), (3, 3));
+        let err = s.parse(String::from("Sab\nabcE\n")).err().unwrap();
+        assert_eq!((err.line, err.column), (2, 4));
+
+        let input = s.parse(String::from("Sabz\nabzE\n")).unwrap();
+        assert_eq!(s.part1(&input).to_string(), "no route");
+        assert_eq!(format!("{:#}", s.part2(&input)), "no route");
+    }
+
+    #[test]
+    fn a_star_expands_fewer_squares() {
+        let s = Solution {};
+        for seed in 0..3 {
+            let raw = s.generate(&mut StdRng::seed_from_u64(seed), 60);
+            let input = s.parse(raw.unwrap()).unwrap();
+            let hill = Hill { map: &input.map };
+            // Each step moves one square and climbs at most one.
+            let end = (input.end.row, input.end.col);
+            let heuristic = |at: &Position| {
+                manhattan((at.row, at.col), end).max(usize::from(b'z' - hill.height(*at)))
+            };
+            let dijkstra = a_star(&hill, input.start, input.end, |_| 0);
+            let guided = a_star(&hill, input.start, input.end, heuristic);
+            assert_eq!(guided.path.unwrap().cost, dijkstra.path.unwrap().cost);
+            assert!(guided.expanded < dijkstra.expanded);
+        }
     }
 }
@@ -1,4 +1,7 @@
 use crate::harness::Harness;
+use crate::parse::{lines, ParseError};
+use rand::rngs::StdRng;
+use rand::Rng;
 use std::cmp::Ordering;
 use std::str::FromStr;
 
@@ -9,20 +12,24 @@ impl Harness for Solution {
     type Part1Output = usize;
     type Part2Output = usize;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
-        let mut lines = raw_input.lines();
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
+        let mut lines = lines(&raw_input);
         let mut packets = Vec::new();
 
-        while let Some((s1, s2)) = lines.next().zip(lines.next()) {
-            let p1: Packet = s1.parse().unwrap();
-            let p2: Packet = s2.parse().unwrap();
-            packets.push(p1);
-            packets.push(p2);
+        while let Some(l1) = lines.next() {
+            let l2 = lines.expect("a second packet")?;
+            for line in [l1, l2] {
+                let packet = line
+                    .text
+                    .parse()
+                    .map_err(|(i, message)| line.error_at(i, message))?;
+                packets.push(packet);
+            }
 
             lines.next();
         }
 
-        packets
+        Ok(packets)
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
@@ -54,6 +61,50 @@ impl Harness for Solution {
             })
             .product()
     }
+
+    /// `size` pairs of packets.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        let mut out = String::new();
+        for _ in 0..size.max(1) {
+            for _ in 0..2 {
+                // Copies of the divider packets would throw part 2 off.
+                let packet = loop {
+                    let mut packet = String::new();
+                    let depth = rng.gen_range(0..=6);
+                    write_list(rng, &mut packet, depth);
+                    if packet != "[[2]]" && packet != "[[6]]" {
+                        break packet;
+                    }
+                };
+                out += &packet;
+                out.push('\n');
+            }
+            out.push('\n');
+        }
+        Some(out)
+    }
+}
+
+/// Writes a random list nesting up to `depth` more lists, except for the
+/// occasional long chain of single item lists.
+fn write_list(rng: &mut StdRng, out: &mut String, depth: u32) {
+    out.push('[');
+    for i in 0..rng.gen_range(0..=4) {
+        if i > 0 {
+            out.push(',');
+        }
+        if rng.gen_bool(0.05) {
+            let links = rng.gen_range(8..=64);
+            out.push_str(&"[".repeat(links));
+            out.push_str(&rng.gen_range(0..=10).to_string());
+            out.push_str(&"]".repeat(links));
+        } else if depth > 0 && rng.gen_bool(0.5) {
+            write_list(rng, out, depth - 1);
+        } else {
+            out.push_str(&rng.gen_range(0..=10).to_string());
+        }
+    }
+    out.push(']');
 }
 
 #[derive(Eq, PartialEq, Debug, Clone)]
@@ -63,63 +114,86 @@ pub enum Packet {
 }
 
 impl FromStr for Packet {
-    type Err = ();
+    /// Byte index and description of the first malformed character.
+    type Err = (usize, &'static str);
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const UNEXPECTED_CLOSE: &str = "unexpected `]`";
+        const UNEXPECTED_COMMA: &str = "unexpected `,`";
+        const EXPECTED_COMMA: &str = "expected `,` or `]`";
+
+        // The whole packet has been read once the outermost list closes.
+        let close = |i: usize, packets| match &s[i + 1..] {
+            "" => Ok(Packet::List(packets)),
+            _ => Err((i + 1, "unexpected text after packet")),
+        };
+
         let mut state = ParseState::Start;
-        for c in s.chars() {
+        let mut prev = None;
+        for (i, c) in s.char_indices() {
+            // An element starts the packet, a list, or follows a comma.
+            let element_ok = matches!(prev, None | Some('[') | Some(','));
             match (c, state) {
+                (']', _) if prev == Some(',') => return Err((i, UNEXPECTED_CLOSE)),
+                ('[', _) if !element_ok => return Err((i, EXPECTED_COMMA)),
                 ('[', prev_state) => {
                     state = ParseState::InList(Vec::new(), Box::new(prev_state));
                 }
                 (']', ParseState::InList(packets, prev_state)) => match *prev_state {
-                    ParseState::Start => return Ok(Packet::List(packets)),
+                    ParseState::Start => return close(i, packets),
                     ParseState::InList(mut pk, prev_state) => {
                         pk.push(Packet::List(packets));
                         state = ParseState::InList(pk, prev_state);
                     }
-                    _ => unreachable!(),
+                    _ => return Err((i, UNEXPECTED_CLOSE)),
                 },
                 (']', ParseState::Number(number, prev_state)) => match *prev_state {
                     ParseState::InList(mut packets, prev_state) => {
                         packets.push(Packet::Int(number));
                         match *prev_state {
-                            ParseState::Start => return Ok(Packet::List(packets)),
+                            ParseState::Start => return close(i, packets),
                             ParseState::InList(mut pk, prev_state) => {
                                 pk.push(Packet::List(packets));
                                 state = ParseState::InList(pk, prev_state);
                             }
-                            ParseState::Number(_, _) => unreachable!(),
+                            ParseState::Number(_, _) => return Err((i, UNEXPECTED_CLOSE)),
                         }
                     }
-                    _ => unreachable!(),
+                    _ => return Err((i, UNEXPECTED_CLOSE)),
                 },
+                (']', ParseState::Start) => return Err((i, UNEXPECTED_CLOSE)),
                 (',', ParseState::Number(number, prev_state)) => match *prev_state {
                     ParseState::InList(mut packets, prev_state) => {
                         packets.push(Packet::Int(number));
                         state = ParseState::InList(packets, prev_state);
                     }
-                    _ => unreachable!(),
+                    _ => return Err((i, UNEXPECTED_COMMA)),
                 },
-                (',', s) => {
+                // Only a closed list may be followed by a comma here.
+                (',', s @ ParseState::InList(_, _)) if prev == Some(']') => {
                     state = s;
                 }
+                (',', _) => return Err((i, UNEXPECTED_COMMA)),
                 (n @ '0'..='9', ParseState::Number(number, prev_state)) => {
                     let digit = n.to_digit(10).unwrap();
-                    state = ParseState::Number(number * 10 + digit, prev_state);
+                    let number = number
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add(digit))
+                        .ok_or((i, "number too large"))?;
+                    state = ParseState::Number(number, prev_state);
                 }
+                ('0'..='9', ParseState::Start) => return Err((i, "expected `[`")),
+                ('0'..='9', _) if !element_ok => return Err((i, EXPECTED_COMMA)),
                 (n @ '0'..='9', prev_state) => {
                     let digit = n.to_digit(10).unwrap();
                     state = ParseState::Number(digit, Box::new(prev_state));
                 }
-                _ => unreachable!(),
+                _ => return Err((i, "expected `[`, `]`, `,` or a digit")),
             }
+            prev = Some(c);
         }
 
-        match state {
-            ParseState::InList(l, _) => Ok(Packet::List(l)),
-            _ => unreachable!(),
-        }
+        Err((s.len(), "unterminated packet"))
     }
 }
 
@@ -170,7 +244,7 @@ mod tests {
     fn part1_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day13-1").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         assert_eq!(s.part1(&input), 13);
     }
 
@@ -178,7 +252,36 @@ mod tests {
     fn part2_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day13-1").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         assert_eq!(s.part2(&input), 140);
     }
+
+    #[test]
+    fn malformed_packet() {
+        let s = Solution {};
+        let err = s.parse(String::from("[1,[2]]\n[1,x]\n")).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        let err = s.parse(String::from("[1,[2]]\n[[1]\n")).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 5));
+
+        for (line, column, message) in [
+            ("[1,99999999999]", 13, "number too large"),
+            ("[1]]x", 4, "unexpected text after packet"),
+            ("[1]x", 4, "unexpected text after packet"),
+            ("[,1]", 2, "unexpected `,`"),
+            ("[1,,2]", 4, "unexpected `,`"),
+            (",[1]", 1, "unexpected `,`"),
+            ("[1,]", 4, "unexpected `]`"),
+            ("[[1][2]]", 5, "expected `,` or `]`"),
+            ("[[1]2]", 5, "expected `,` or `]`"),
+        ] {
+            let err = s.parse(format!("{}\n[1]\n", line)).unwrap_err();
+            assert_eq!(
+                (err.line, err.column, err.message.as_str()),
+                (1, column, message),
+                "{}",
+                line
+            );
+        }
+    }
 }
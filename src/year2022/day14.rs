@@ -1,25 +1,54 @@
 use crate::harness::Harness;
+use crate::params::{Param, Params};
+use crate::parse::{lines, ParseError};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
 use std::collections::HashSet;
 
 pub struct Solution;
 
+const SOURCE_X: Param = Param {
+    name: "source_x",
+    default: 500,
+    min: 0,
+    max: 1_000_000,
+    help: "column the sand pours in from",
+};
+const SOURCE_Y: Param = Param {
+    name: "source_y",
+    default: 0,
+    min: 0,
+    max: 1_000_000,
+    help: "row the sand pours in from",
+};
+
+/// Where the sand starts. Column 0 is a wall it cannot slide past, should
+/// an overridden source put it there.
+fn source(params: &Params) -> (u32, u32) {
+    (params.get(&SOURCE_X) as u32, params.get(&SOURCE_Y) as u32)
+}
+
 impl Harness for Solution {
     type Parsed = HashSet<(u32, u32)>;
     type Part1Output = usize;
     type Part2Output = usize;
 
-    fn parse(&self, raw_input: String) -> Self::Parsed {
+    const PARAMS: &'static [Param] = &[SOURCE_X, SOURCE_Y];
+
+    fn parse(&self, raw_input: String) -> Result<Self::Parsed, ParseError> {
         let mut points = HashSet::new();
-        for line in raw_input.lines() {
-            let vertices: Vec<_> = line
+        for line in lines(&raw_input) {
+            let vertices = line
+                .text
                 .split(" -> ")
                 .map(|segment| {
                     let mut parts = segment.split(',');
-                    let x: u32 = parts.next().unwrap().parse().unwrap();
-                    let y: u32 = parts.next().unwrap().parse().unwrap();
-                    (x, y)
+                    let x: u32 = line.parse(line.expect(&mut parts, "an x coordinate")?)?;
+                    let y: u32 = line.parse(line.expect(&mut parts, "a y coordinate")?)?;
+                    Ok((x, y))
                 })
-                .collect();
+                .collect::<Result<Vec<_>, ParseError>>()?;
 
             for window in vertices.windows(2) {
                 let x_min = window[0].0.min(window[1].0);
@@ -34,16 +63,25 @@ impl Harness for Solution {
             }
         }
 
-        points
+        Ok(points)
     }
 
     fn part1(&self, input: &Self::Parsed) -> Self::Part1Output {
+        self.part1_with(input, &Params::default())
+    }
+
+    fn part2(&self, input: &Self::Parsed) -> Self::Part2Output {
+        self.part2_with(input, &Params::default())
+    }
+
+    fn part1_with(&self, input: &Self::Parsed, params: &Params) -> Self::Part1Output {
+        let start = source(params);
         let mut occupied: HashSet<(u32, u32)> = input.clone();
         let max_y = *occupied.iter().map(|(_, y)| y).max().unwrap();
 
-        'outer: loop {
-            const START: (u32, u32) = (500, 0);
-            let (mut x, mut y) = START;
+        // Sand resting at the source blocks it before any falls into the abyss.
+        'outer: while !occupied.contains(&start) {
+            let (mut x, mut y) = start;
 
             loop {
                 if !occupied.contains(&(x, y + 1)) {
@@ -51,7 +89,7 @@ impl Harness for Solution {
                     if y > max_y {
                         break 'outer;
                     }
-                } else if !occupied.contains(&(x - 1, y + 1)) {
+                } else if x > 0 && !occupied.contains(&(x - 1, y + 1)) {
                     x -= 1;
                     y += 1;
                 } else if !occupied.contains(&(x + 1, y + 1)) {
@@ -68,19 +106,19 @@ impl Harness for Solution {
         occupied.len() - input.len()
     }
 
-    fn part2(&self, input: &Self::Parsed) -> Self::Part2Output {
+    fn part2_with(&self, input: &Self::Parsed, params: &Params) -> Self::Part2Output {
+        let start = source(params);
         let mut occupied: HashSet<(u32, u32)> = input.clone();
         let max_y = *occupied.iter().map(|(_, y)| y).max().unwrap();
         let floor = max_y + 2;
 
         loop {
-            const START: (u32, u32) = (500, 0);
-            let (mut x, mut y) = START;
+            let (mut x, mut y) = start;
 
             loop {
                 if !occupied.contains(&(x, y + 1)) && y + 1 < floor {
                     y += 1;
-                } else if !occupied.contains(&(x - 1, y + 1)) && y + 1 < floor {
+                } else if x > 0 && !occupied.contains(&(x - 1, y + 1)) && y + 1 < floor {
                     x -= 1;
                     y += 1;
                 } else if !occupied.contains(&(x + 1, y + 1)) && y + 1 < floor {
@@ -92,13 +130,82 @@ impl Harness for Solution {
             }
 
             occupied.insert((x, y));
-            if (x, y) == START {
+            if (x, y) == start {
                 break;
             }
         }
 
         occupied.len() - input.len()
     }
+
+    /// `size` rock paths under the default source, about as dense as in
+    /// the puzzle and drawn again if part 1's sand would pile up to the
+    /// source instead of falling past them. The sand of part 2 must stay
+    /// clear of `x = 0`, which bounds how deep the rocks can go, so past a
+    /// few thousand paths the rest repeat earlier ones, as the puzzle's own
+    /// paths do.
+    fn generate(&self, rng: &mut StdRng, size: usize) -> Option<String> {
+        const CELLS_PER_PATH: usize = 80;
+        let size = size.max(1);
+        let depth = ((CELLS_PER_PATH * size) as f64).sqrt().clamp(20.0, 480.0) as u32;
+        let distinct = size.min((depth * depth) as usize / CELLS_PER_PATH);
+        let (top, bottom) = (13, 13 + depth);
+        let (left, right) = (500 - depth / 2, 500 + depth / 2);
+        loop {
+            let mut paths: Vec<String> = (0..distinct)
+                .map(|_| {
+                    let mut x = rng.gen_range(left..=right);
+                    let mut y = rng.gen_range(top..=bottom);
+                    let mut vertices = vec![format!("{},{}", x, y)];
+                    let horizontal_first: bool = rng.gen();
+                    for i in 0..rng.gen_range(1..=5) {
+                        let step = rng.gen_range(1..=8);
+                        if (i % 2 == 0) == horizontal_first {
+                            x = if rng.gen() { x + step } else { x - step };
+                        } else {
+                            y = if rng.gen() { y + step } else { y - step };
+                            y = y.clamp(top, bottom);
+                        }
+                        vertices.push(format!("{},{}", x, y));
+                    }
+                    vertices.join(" -> ") + "\n"
+                })
+                .collect();
+
+            let rocks = self.parse(paths.concat()).expect("generated paths parse");
+            if !fills_to_source(&rocks) {
+                for i in distinct..size {
+                    paths.push(paths[i % distinct].clone());
+                }
+                paths.shuffle(rng);
+                return Some(paths.concat());
+            }
+        }
+    }
+}
+
+/// Pours sand as part 1 does, `true` if it comes to rest at the default
+/// source before any falls past the rocks, so that part 1 never ends.
+fn fills_to_source(rocks: &HashSet<(u32, u32)>) -> bool {
+    let start = (SOURCE_X.default as u32, SOURCE_Y.default as u32);
+    let mut occupied = rocks.clone();
+    let max_y = *occupied.iter().map(|(_, y)| y).max().unwrap();
+    loop {
+        let (mut x, mut y) = start;
+        while let Some(next) = [(x, y + 1), (x - 1, y + 1), (x + 1, y + 1)]
+            .into_iter()
+            .find(|p| !occupied.contains(p))
+        {
+            (x, y) = next;
+            if y > max_y {
+                return false;
+            }
+        }
+        if (x, y) == start {
+            return true;
+        }
+        occupied.insert((x, y));
+    }
 }
 
 #[cfg(test)]
@@ -110,7 +217,7 @@ mod tests {
     fn part1_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day14-1").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         assert_eq!(s.part1(&input), 24);
     }
 
@@ -118,7 +225,20 @@ mod tests {
     fn part2_sample1() {
         let s = Solution {};
         let raw = read_to_string("samples/2022/day14-1").unwrap();
-        let input = s.parse(raw);
+        let input = s.parse(raw).unwrap();
         assert_eq!(s.part2(&input), 93);
     }
+
+    #[test]
+    fn part1_blocked_source() {
+        let s = Solution {};
+        let raw = read_to_string("samples/2022/day14-1").unwrap();
+        let input = s.parse(raw).unwrap();
+        // Just above the rocks, the first grain rests at the source.
+        let params = Params::default().with("source_x", 500).with("source_y", 8);
+        assert_eq!(s.part1_with(&input, &params), 1);
+        // In the rocks, none falls at all.
+        let params = params.with("source_y", 9);
+        assert_eq!(s.part1_with(&input, &params), 0);
+    }
 }
@@ -0,0 +1,26 @@
+use advent_of_code_2022::{solve, SolveError};
+use std::fs::read_to_string;
+
+#[test]
+fn solves_samples() {
+    let raw = read_to_string("samples/2022/day08-1").unwrap();
+    assert_eq!(
+        solve(2022, 8, &raw),
+        Ok((String::from("21"), String::from("8")))
+    );
+}
+
+#[test]
+fn reports_errors() {
+    assert_eq!(
+        solve(2022, 26, ""),
+        Err(SolveError::UnknownDay {
+            year: 2022,
+            day: 26
+        })
+    );
+    assert!(matches!(
+        solve(2022, 8, "12\nx4\n"),
+        Err(SolveError::Parse(_))
+    ));
+}